@@ -1,28 +1,293 @@
-use crate::WindowHandle;
+use crate::{
+    CallbackRegistry, ReturnErrorCode, SemaphoreSignaller, WindowConfig, WindowHandle,
+    WindowListeners, WinitError, WinitErrorCode, WinitUserEvent, WinitWindowAttributes,
+    WinitWindowInitialState,
+};
+use std::fmt::{Debug, Formatter};
 use std::os::raw::c_void;
+use std::sync::mpsc::{Receiver, Sender};
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
 use winit::dpi::Size;
-use winit::window::{WindowAttributes, WindowId};
+use winit::window::WindowId;
 
 pub enum ApplicationAction {
     FunctionCall(FunctionCallAction),
     CreateWindow(CreateWindowAction),
     RequestWindowSurfaceSize(RequestWindowSurfaceSizeAction),
+    ApplyWindowConfig(ApplyWindowConfigAction),
+    RecreateWindow(RecreateWindowAction),
+    FocusWindow(FocusWindowAction),
+    /// Shrink the windows registry
+    TrimMemory,
+    PushUserEvent(PushUserEventAction),
+    Acknowledged(AcknowledgedAction),
+    #[cfg(feature = "test-harness")]
+    InjectWindowEvent(InjectWindowEventAction),
 }
 
+impl Debug for ApplicationAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FunctionCall(_) => f.write_str("FunctionCall"),
+            Self::CreateWindow(_) => f.write_str("CreateWindow"),
+            Self::RequestWindowSurfaceSize(_) => f.write_str("RequestWindowSurfaceSize"),
+            Self::ApplyWindowConfig(_) => f.write_str("ApplyWindowConfig"),
+            Self::RecreateWindow(_) => f.write_str("RecreateWindow"),
+            Self::FocusWindow(_) => f.write_str("FocusWindow"),
+            Self::TrimMemory => f.write_str("TrimMemory"),
+            Self::PushUserEvent(_) => f.write_str("PushUserEvent"),
+            Self::Acknowledged(action) => write!(f, "Acknowledged({:?})", action.action),
+            #[cfg(feature = "test-harness")]
+            Self::InjectWindowEvent(_) => f.write_str("InjectWindowEvent"),
+        }
+    }
+}
+
+impl ApplicationAction {
+    /// Cursor, focus and redraw-affecting actions overtake the queued normal actions,
+    /// such as background function calls, so that interaction stays responsive
+    pub fn is_high_priority(&self) -> bool {
+        match self {
+            Self::RequestWindowSurfaceSize(_)
+            | Self::ApplyWindowConfig(_)
+            | Self::FocusWindow(_) => true,
+            Self::Acknowledged(action) => action.action.is_high_priority(),
+            _ => false,
+        }
+    }
+
+    /// Fire the acknowledgement once the action was executed on the UI thread
+    pub fn acknowledged(self, acknowledgement: ActionAcknowledgement) -> Self {
+        Self::Acknowledged(AcknowledgedAction {
+            action: Box::new(self),
+            acknowledgement,
+        })
+    }
+}
+
+#[derive(Debug)]
 pub struct FunctionCallAction {
     pub callback: unsafe extern "C" fn(*const c_void),
     pub thunk: *const c_void,
+    pub token: usize,
 }
 
 unsafe impl Send for FunctionCallAction {}
 unsafe impl Sync for FunctionCallAction {}
 
+impl FunctionCallAction {
+    pub fn new(callback: unsafe extern "C" fn(*const c_void), thunk: *const c_void) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    pub fn call(&self) {
+        if CallbackRegistry::is_valid(self.token) {
+            trace_span!("function_call");
+            unsafe { (self.callback)(self.thunk) };
+        }
+    }
+}
+
 pub struct CreateWindowAction {
-    pub window_attributes: WindowAttributes,
-    pub callback: Box<dyn FnOnce(WindowHandle) + 'static>,
+    pub window_attributes: WinitWindowAttributes,
+    pub focus: bool,
+    /// Registered before any event can reach the window
+    pub listeners: WindowListeners,
+    /// Also receives the ids of the registered listeners, redraw listeners first
+    pub callback:
+        Box<dyn FnOnce(WindowHandle, WinitWindowInitialState, Vec<usize>) + Send + 'static>,
+    /// Called instead of the callback when the window can not be created
+    pub error_callback: Option<Box<dyn FnOnce(WinitErrorCode) + Send + 'static>>,
+}
+
+/// Lets the host sequence operations, for example resize, reposition and then show a window,
+/// by waiting for each action to complete on the UI thread
+#[derive(Debug)]
+pub enum ActionAcknowledgement {
+    Semaphore(SemaphoreSignaller),
+    Callback(FunctionCallAction),
+}
+
+impl ActionAcknowledgement {
+    pub fn acknowledge(&self) {
+        match self {
+            Self::Semaphore(semaphore) => semaphore.signal(),
+            Self::Callback(callback) => callback.call(),
+        }
+    }
+}
+
+/// Take the acknowledgement out of its box before anything else can fail and pass it to the
+/// request. If the request fails before it takes the acknowledgement, for example because the
+/// window is already released, the acknowledgement is fired on the calling thread
+pub(crate) fn with_acknowledgement(
+    acknowledgement: *mut ValueBox<ActionAcknowledgement>,
+    request: impl FnOnce(&mut Option<ActionAcknowledgement>) -> Result<(), WinitError>,
+) -> WinitErrorCode {
+    let mut acknowledgement = match acknowledgement.take_value() {
+        Ok(acknowledgement) => Some(acknowledgement),
+        Err(error) => return WinitError::from(error).report(),
+    };
+    let result = request(&mut acknowledgement);
+    if let Some(acknowledgement) = acknowledgement {
+        acknowledgement.acknowledge();
+    }
+    result.into_error_code()
+}
+
+pub struct AcknowledgedAction {
+    pub action: Box<ApplicationAction>,
+    pub acknowledgement: ActionAcknowledgement,
 }
 
 pub struct RequestWindowSurfaceSizeAction {
     pub surface_size: Size,
     pub window_id: WindowId,
 }
+
+pub struct ApplyWindowConfigAction {
+    pub config: WindowConfig,
+    pub window_id: WindowId,
+}
+
+pub struct RecreateWindowAction {
+    pub window_id: WindowId,
+}
+
+pub struct FocusWindowAction {
+    pub window_id: WindowId,
+}
+
+pub struct PushUserEventAction {
+    pub window_id: WindowId,
+    pub event: WinitUserEvent,
+}
+
+/// Deliver a synthetic event to a window as if it came from the platform
+#[cfg(feature = "test-harness")]
+pub struct InjectWindowEventAction {
+    pub window_id: WindowId,
+    pub event: winit::event::WindowEvent,
+}
+
+/// The two lanes actions are sent to the UI thread on
+#[derive(Debug, Clone)]
+pub struct ActionLanes {
+    pub normal: Sender<ApplicationAction>,
+    /// Handled before the rest of the queue
+    pub priority: Sender<ApplicationAction>,
+}
+
+impl ActionLanes {
+    /// High priority actions take the priority lane when there is one, a deferred event loop
+    /// only waits for the normal lane until it is initialized
+    pub fn lane_of(
+        &self,
+        action: &ApplicationAction,
+        has_priority_lane: bool,
+    ) -> &Sender<ApplicationAction> {
+        if has_priority_lane && action.is_high_priority() {
+            &self.priority
+        } else {
+            &self.normal
+        }
+    }
+}
+
+/// The next action to handle on the UI thread, priority actions that arrived meanwhile do not
+/// wait for the rest of the queue
+pub fn next_action(
+    priority: &Receiver<ApplicationAction>,
+    normal: &Receiver<ApplicationAction>,
+) -> Option<ApplicationAction> {
+    priority.try_recv().or_else(|_| normal.try_recv()).ok()
+}
+
+/// An acknowledgement that signals the semaphore, taking ownership of the signaller
+#[no_mangle]
+pub extern "C" fn winit_action_acknowledgement_new_semaphore(
+    semaphore: *mut ValueBox<SemaphoreSignaller>,
+) -> *mut ValueBox<ActionAcknowledgement> {
+    semaphore
+        .take_value()
+        .map(|semaphore| value_box!(ActionAcknowledgement::Semaphore(semaphore)).into_raw())
+        .or_log(std::ptr::null_mut())
+}
+
+/// An acknowledgement that calls the callback with the thunk on the UI thread
+#[no_mangle]
+pub extern "C" fn winit_action_acknowledgement_new_callback(
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+) -> *mut ValueBox<ActionAcknowledgement> {
+    value_box!(ActionAcknowledgement::Callback(FunctionCallAction::new(
+        callback, thunk
+    )))
+    .into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn winit_action_acknowledgement_release(
+    acknowledgement: *mut ValueBox<ActionAcknowledgement>,
+) {
+    acknowledgement.release();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    unsafe extern "C" fn background_call(_thunk: *const c_void) {}
+
+    #[test]
+    fn handles_focus_before_queued_background_calls() {
+        let (normal, normal_receiver) = mpsc::channel();
+        let (priority, priority_receiver) = mpsc::channel();
+        let lanes = ActionLanes { normal, priority };
+        let window_id = WindowId::from_raw(1);
+
+        let mut actions: Vec<ApplicationAction> = (0..100)
+            .map(|_| {
+                ApplicationAction::FunctionCall(FunctionCallAction::new(
+                    background_call,
+                    std::ptr::null(),
+                ))
+            })
+            .collect();
+        actions.push(ApplicationAction::FocusWindow(FocusWindowAction {
+            window_id,
+        }));
+        for action in actions {
+            lanes.lane_of(&action, true).send(action).unwrap();
+        }
+
+        assert!(matches!(
+            next_action(&priority_receiver, &normal_receiver),
+            Some(ApplicationAction::FocusWindow(_))
+        ));
+        assert!(matches!(
+            next_action(&priority_receiver, &normal_receiver),
+            Some(ApplicationAction::FunctionCall(_))
+        ));
+    }
+
+    #[test]
+    fn keeps_the_order_without_a_priority_lane() {
+        let (normal, normal_receiver) = mpsc::channel();
+        let (priority, priority_receiver) = mpsc::channel();
+        let lanes = ActionLanes { normal, priority };
+
+        let action = ApplicationAction::FocusWindow(FocusWindowAction {
+            window_id: WindowId::from_raw(1),
+        });
+        lanes.lane_of(&action, false).send(action).unwrap();
+
+        assert!(priority_receiver.try_recv().is_err());
+        assert!(normal_receiver.try_recv().is_ok());
+    }
+}
@@ -1,23 +1,55 @@
 use crate::{
-    convert_event, ApplicationAction, ApplicationEvents, CreateWindowAction, FunctionCallAction,
-    SemaphoreSignaller, WakeUpSignaller, WindowHandle, WinitEventType, WinitWindowEvent,
+    convert_device_event, convert_event, next_action, offscreen_window_position, track_num_lock,
+    window_event_mask, ActionAcknowledgement, ActionLanes, ApplicationAction, ApplicationEvents,
+    ApplicationSettings, CallbackRegistry, CreateWindowAction, DeviceIds, EventBatch,
+    EventCallback, EventClock, EventLoopThreadSettings, EventQueueWatchdog,
+    EventQueueWatchdogCallback, EventSinkHandle, EventSinks, EventSubscriptions,
+    FunctionCallAction, HostClock, LatencyProbe, LatencyProbeCallback, LivenessCallback,
+    LivenessNotifier, PushUserEventAction, RedrawThrottle, ReturnErrorCode, ScreenArea,
+    SemaphoreSignaller, WakeUpSignaller, WindowHandle, WindowListeners,
+    WindowRedrawRequestedListener, WindowResizedListener, WinitActionFailedEvent,
+    WinitActionFailure, WinitActionType, WinitCallbackKind, WinitCallbackOverBudgetEvent,
+    WinitCapabilities, WinitError, WinitErrorCode, WinitEvent, WinitEventModifiersChanged,
+    WinitEventRecord, WinitEventSinkMode, WinitEventType, WinitLatencyReport,
+    WinitPointerEventMode, WinitResizeCause, WinitSyntheticKeyPolicy, WinitTextEncoding,
+    WinitTextSource, WinitThreadPriority, WinitUserEvent, WinitWindowAttributes, WinitWindowEvent,
+    WinitWindowInitialState, WinitWindowMonitorChangedEvent, WinitWindowRecreatedEvent,
+    WinitWindowRepositionedEvent, WinitWindowStateChangedEvent,
 };
+#[cfg(windows_platform)]
+use crate::{Win32MessageHook, Win32Msg};
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use raw_window_handle_extensions::{VeryRawDisplayHandle, VeryRawWindowHandle};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
 use std::os::raw::c_void;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::{Duration, Instant};
+use string_box::StringBox;
 use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
 use winit::application::ApplicationHandler;
-use winit::event::WindowEvent;
-use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopBuilder, EventLoopProxy};
+use winit::dpi::Position;
+use winit::event::{DeviceEvent, DeviceId, KeyEvent, WindowEvent};
+use winit::event_loop::{
+    ActiveEventLoop, ControlFlow, DeviceEvents, EventLoop, EventLoopBuilder, EventLoopProxy,
+};
 use winit::raw_window_handle::{HasDisplayHandle, RawDisplayHandle};
-use winit::window::{WindowAttributes, WindowId};
+use winit::window::WindowId;
+
+/// How often the monitors are compared to find windows left off-screen
+const MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct ApplicationBuilder {
     event_loop_builder: EventLoopBuilder,
     semaphore_signaller: Option<SemaphoreSignaller>,
     wakeup_signallers: Mutex<Vec<WakeUpSignaller>>,
+    lazy_initialization: bool,
+    thread_settings: EventLoopThreadSettings,
+    minimum_idle_sleep: Duration,
+    #[cfg(windows_platform)]
+    msg_hook: Option<Arc<Win32MessageHook>>,
 }
 
 impl ApplicationBuilder {
@@ -35,6 +67,11 @@ impl ApplicationBuilder {
             event_loop_builder,
             semaphore_signaller: None,
             wakeup_signallers: Default::default(),
+            lazy_initialization: false,
+            thread_settings: Default::default(),
+            minimum_idle_sleep: Duration::ZERO,
+            #[cfg(windows_platform)]
+            msg_hook: None,
         }
     }
 
@@ -44,6 +81,40 @@ impl ApplicationBuilder {
         self.event_loop_builder.with_android_app(app);
     }
 
+    /// Let the host see the messages of the UI thread and of its windows, posted or sent,
+    /// before winit handles them. When the hook returns true the message is considered handled
+    /// and is not passed on to winit.
+    #[cfg(windows_platform)]
+    pub fn with_msg_hook(&mut self, hook: Win32MessageHook) {
+        use winit::platform::windows::EventLoopBuilderExtWindows;
+        let hook = Arc::new(hook);
+        let thread_hook = hook.clone();
+        self.event_loop_builder
+            .with_msg_hook(move |msg| thread_hook.on_thread_message(msg as *const Win32Msg));
+        self.msg_hook = Some(hook);
+    }
+
+    /// Let the event loop run on a thread other than the main one, as the tests do.
+    /// Not possible on macOS, where the event loop must run on the main thread
+    #[cfg(feature = "test-harness")]
+    pub fn with_any_thread(&mut self) {
+        #[cfg(x11_platform)]
+        {
+            use winit::platform::x11::EventLoopBuilderExtX11;
+            self.event_loop_builder.with_any_thread(true);
+        }
+        #[cfg(wayland_platform)]
+        {
+            use winit::platform::wayland::EventLoopBuilderExtWayland;
+            self.event_loop_builder.with_any_thread(true);
+        }
+        #[cfg(windows_platform)]
+        {
+            use winit::platform::windows::EventLoopBuilderExtWindows;
+            self.event_loop_builder.with_any_thread(true);
+        }
+    }
+
     pub fn add_wakeup_signaller(&self, wake_up_signaller: WakeUpSignaller) {
         self.wakeup_signallers.lock().push(wake_up_signaller);
     }
@@ -52,151 +123,964 @@ impl ApplicationBuilder {
         self.semaphore_signaller = Some(semaphore);
     }
 
+    /// Defer the connection to the windowing system until the first window is requested,
+    /// for sessions that may never open a native window
+    pub fn set_lazy_initialization(&mut self, lazy_initialization: bool) {
+        self.lazy_initialization = lazy_initialization;
+    }
+
+    /// Name the thread that runs the event loop, as seen by debuggers and profilers
+    pub fn set_thread_name(&mut self, name: impl Into<String>) {
+        self.thread_settings.name = Some(name.into());
+    }
+
+    pub fn set_thread_priority(&mut self, priority: WinitThreadPriority) {
+        self.thread_settings.priority = priority;
+    }
+
+    /// While windows are redrawn continuously, make sure the event loop waits at least this long
+    /// between the redraws requested by the host, trading a bounded amount of latency for CPU time.
+    /// Disabled with a zero duration, which is the default
+    pub fn set_minimum_idle_sleep(&mut self, minimum_idle_sleep: Duration) {
+        self.minimum_idle_sleep = minimum_idle_sleep;
+    }
+
     pub fn build(mut self) -> anyhow::Result<(Application, ApplicationHandle)> {
         let (sender, receiver) = mpsc::channel();
-        let event_loop = self.event_loop_builder.build()?;
-        let display_handle = event_loop.display_handle()?.as_raw();
+        let (priority_sender, priority_receiver) = mpsc::channel();
+        let backend: Arc<OnceLock<EventLoopBackend>> = Default::default();
+
+        let event_loop = if self.lazy_initialization {
+            ApplicationEventLoop::Deferred(self.event_loop_builder)
+        } else {
+            ApplicationEventLoop::Ready(build_event_loop(&mut self.event_loop_builder, &backend)?)
+        };
 
         let events = ApplicationEvents::new();
 
         let application_handle = ApplicationHandle {
-            sender,
-            event_loop: event_loop.create_proxy(),
+            actions: ActionLanes {
+                normal: sender,
+                priority: priority_sender,
+            },
+            backend,
             events,
-            event_loop_type: WinitEventLoopType::from(display_handle),
+            semaphore_signaller: self.semaphore_signaller.map(Arc::new),
+            settings: Default::default(),
+            clock: Default::default(),
+            latency_probe: Default::default(),
+            subscriptions: Default::default(),
+            device_ids: Default::default(),
+            liveness: Default::default(),
+            sinks: Default::default(),
+            event_callback: Default::default(),
+            num_lock: Default::default(),
+            modifiers: Default::default(),
+            windows_memory_usage: Default::default(),
+            redraw_throttle: Arc::new(Mutex::new(RedrawThrottle::new(self.minimum_idle_sleep))),
+            #[cfg(windows_platform)]
+            msg_hook: self.msg_hook,
         };
 
         let application = Application {
             event_loop,
             application_handle: application_handle.clone(),
             receiver,
-            semaphore_signaller: self.semaphore_signaller,
+            priority_receiver,
             wakeup_signallers: self.wakeup_signallers,
+            thread_settings: self.thread_settings,
         };
 
         Ok((application, application_handle))
     }
 }
 
+fn build_event_loop(
+    event_loop_builder: &mut EventLoopBuilder,
+    backend: &OnceLock<EventLoopBackend>,
+) -> anyhow::Result<EventLoop> {
+    let event_loop = event_loop_builder.build()?;
+    let display_handle = event_loop.display_handle()?.as_raw();
+
+    let event_loop_type = WinitEventLoopType::from(display_handle);
+    let capabilities = WinitCapabilities::probe(event_loop_type, display_handle);
+    info!(
+        "Event loop runs on {:?} with {:?}",
+        event_loop_type, capabilities
+    );
+
+    let _ = backend.set(EventLoopBackend {
+        proxy: event_loop.create_proxy(),
+        event_loop_type,
+        capabilities,
+        display_handle: EventLoopDisplayHandle(display_handle),
+    });
+    Ok(event_loop)
+}
+
+/// The parts of the application handle that exist once the event loop is built
+#[derive(Debug)]
+struct EventLoopBackend {
+    proxy: EventLoopProxy,
+    event_loop_type: WinitEventLoopType,
+    capabilities: WinitCapabilities,
+    display_handle: EventLoopDisplayHandle,
+}
+
+enum ApplicationEventLoop {
+    Ready(EventLoop),
+    /// The event loop is built when the first window is requested
+    Deferred(EventLoopBuilder),
+}
+
+impl Debug for ApplicationEventLoop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ready(_) => f.write_str("Ready"),
+            Self::Deferred(_) => f.write_str("Deferred"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Application {
-    event_loop: EventLoop,
+    event_loop: ApplicationEventLoop,
     application_handle: ApplicationHandle,
     receiver: Receiver<ApplicationAction>,
-    semaphore_signaller: Option<SemaphoreSignaller>,
+    priority_receiver: Receiver<ApplicationAction>,
     wakeup_signallers: Mutex<Vec<WakeUpSignaller>>,
+    thread_settings: EventLoopThreadSettings,
 }
 
 impl Application {
     pub fn run(self) {
+        self.thread_settings.apply();
+        let mut pending_actions = vec![];
+
+        let event_loop = match self.event_loop {
+            ApplicationEventLoop::Ready(event_loop) => event_loop,
+            ApplicationEventLoop::Deferred(mut event_loop_builder) => {
+                // function calls do not need the windowing system, they are executed right away
+                // on the UI thread until any other action, such as a window creation, arrives
+                loop {
+                    while let Ok(action) = self.priority_receiver.try_recv() {
+                        match action {
+                            ApplicationAction::FunctionCall(action) => action.call(),
+                            action => pending_actions.push(action),
+                        }
+                    }
+                    if !pending_actions.is_empty() {
+                        break;
+                    }
+                    match self.receiver.recv() {
+                        Ok(ApplicationAction::FunctionCall(action)) => {
+                            action.call();
+                        }
+                        Ok(action) => {
+                            pending_actions.push(action);
+                            break;
+                        }
+                        Err(_) => return,
+                    }
+                }
+                info!("Initializing the event loop");
+                match build_event_loop(&mut event_loop_builder, &self.application_handle.backend) {
+                    Ok(event_loop) => event_loop,
+                    Err(error) => {
+                        WinitError::new(
+                            WinitErrorCode::OsError,
+                            format!("Failed to initialize the event loop: {}", error),
+                        )
+                        .report();
+                        return;
+                    }
+                }
+            }
+        };
+
         let application = RunningApplication {
             receiver: self.receiver,
+            priority_receiver: self.priority_receiver,
+            pending_actions,
             windows: Default::default(),
             application_handle: self.application_handle,
-            semaphore_signaller: self.semaphore_signaller,
             wakeup_signallers: self.wakeup_signallers,
+            listens_unfocused_device_events: false,
+            native_window_ids: Default::default(),
+            lost_window_ids: Default::default(),
+            monitor_areas: None,
+            last_monitor_check: Instant::now(),
         };
 
         info!("Running application: {:?}", application);
         // todo: handle errors
-        self.event_loop.run_app(application).unwrap();
+        event_loop.run_app(application).unwrap();
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ApplicationHandle {
-    sender: Sender<ApplicationAction>,
-    event_loop: EventLoopProxy,
+    /// High priority actions and the ones the host asked to handle first take the priority lane
+    actions: ActionLanes,
+    backend: Arc<OnceLock<EventLoopBackend>>,
     events: ApplicationEvents,
-    event_loop_type: WinitEventLoopType,
+    /// Signalled once events were enqueued
+    semaphore_signaller: Option<Arc<SemaphoreSignaller>>,
+    settings: Arc<Mutex<ApplicationSettings>>,
+    clock: Arc<Mutex<EventClock>>,
+    latency_probe: Arc<Mutex<Option<LatencyProbe>>>,
+    subscriptions: Arc<Mutex<EventSubscriptions>>,
+    device_ids: Arc<Mutex<DeviceIds>>,
+    liveness: Arc<Mutex<Option<LivenessNotifier>>>,
+    sinks: Arc<Mutex<EventSinks>>,
+    event_callback: Arc<Mutex<Option<EventCallback>>>,
+    num_lock: Arc<AtomicBool>,
+    /// The latest modifiers state of the focused window
+    modifiers: Arc<Mutex<WinitEventModifiersChanged>>,
+    /// Published by the UI thread which owns the windows registry
+    windows_memory_usage: Arc<AtomicUsize>,
+    redraw_throttle: Arc<Mutex<RedrawThrottle>>,
+    /// Installed on the window procedure of every created window
+    #[cfg(windows_platform)]
+    msg_hook: Option<Arc<Win32MessageHook>>,
 }
 
 impl ApplicationHandle {
     pub fn create_window(
         &self,
-        window_attributes: WindowAttributes,
-        callback: impl FnOnce(WindowHandle) + 'static,
+        window_attributes: impl Into<WinitWindowAttributes>,
+        callback: impl FnOnce(WindowHandle, WinitWindowInitialState) + Send + 'static,
+    ) {
+        self.enqueue_action(ApplicationAction::CreateWindow(CreateWindowAction {
+            window_attributes: window_attributes.into(),
+            focus: false,
+            listeners: Default::default(),
+            callback: Box::new(move |window_handle, initial_state, _| {
+                callback(window_handle, initial_state)
+            }),
+            error_callback: None,
+        }))
+    }
+
+    /// Create a window with redraw and resize listeners registered before the window
+    /// receives any event, so that the first resize or redraw can not be missed.
+    /// The callback also receives the ids of the listeners, redraw listeners first
+    pub fn create_window_with_listeners(
+        &self,
+        window_attributes: impl Into<WinitWindowAttributes>,
+        listeners: WindowListeners,
+        callback: impl FnOnce(WindowHandle, WinitWindowInitialState, Vec<usize>) + Send + 'static,
     ) {
         self.enqueue_action(ApplicationAction::CreateWindow(CreateWindowAction {
-            window_attributes,
+            window_attributes: window_attributes.into(),
+            focus: false,
+            listeners,
             callback: Box::new(callback),
+            error_callback: None,
+        }))
+    }
+
+    /// Create a window and focus it as part of the same action, on platforms that ignore
+    /// focus requests issued separately right after the creation.
+    pub fn create_focused_window(
+        &self,
+        window_attributes: impl Into<WinitWindowAttributes>,
+        callback: impl FnOnce(WindowHandle, WinitWindowInitialState) + Send + 'static,
+    ) {
+        self.enqueue_action(ApplicationAction::CreateWindow(CreateWindowAction {
+            window_attributes: window_attributes.into(),
+            focus: true,
+            listeners: Default::default(),
+            callback: Box::new(move |window_handle, initial_state, _| {
+                callback(window_handle, initial_state)
+            }),
+            error_callback: None,
         }))
     }
 
     pub fn enqueue_action(&self, action: ApplicationAction) {
-        self.sender.send(action).unwrap();
+        self.lane_of(&action).send(action).unwrap();
+        self.wake_up();
+    }
+
+    /// See [`ApplicationAction::is_high_priority`]
+    fn lane_of(&self, action: &ApplicationAction) -> &Sender<ApplicationAction> {
+        self.actions.lane_of(action, self.backend.get().is_some())
+    }
+
+    /// Let the action overtake the queued normal actions, such as background function calls.
+    /// Until a deferred event loop is initialized there is nothing to wake up, the action
+    /// is then queued in order with the rest, which the UI thread handles as they arrive
+    pub fn enqueue_priority_action(&self, action: ApplicationAction) {
+        if self.backend.get().is_some() {
+            self.actions.priority.send(action).unwrap();
+        } else {
+            self.actions.normal.send(action).unwrap();
+        }
+        self.wake_up();
+    }
+
+    /// Enqueue the action, acknowledged once executed if there is an acknowledgement.
+    /// Once the event loop is gone the acknowledgement is fired right away,
+    /// so that a host waiting for it is not blocked forever
+    pub fn enqueue_acknowledged_action(
+        &self,
+        action: ApplicationAction,
+        acknowledgement: Option<ActionAcknowledgement>,
+    ) -> Result<(), WinitError> {
+        let action = match acknowledgement {
+            Some(acknowledgement) => action.acknowledged(acknowledgement),
+            None => action,
+        };
+        self.lane_of(&action).send(action).map_err(|error| {
+            if let ApplicationAction::Acknowledged(action) = error.0 {
+                action.acknowledgement.acknowledge();
+            }
+            event_loop_closed()
+        })?;
+        self.wake_up();
+        Ok(())
+    }
+
+    /// Same as [`ApplicationHandle::enqueue_action`], but fails instead of panicking
+    /// once the event loop is gone
+    pub fn try_enqueue_action(&self, action: ApplicationAction) -> Result<(), WinitError> {
+        self.lane_of(&action)
+            .send(action)
+            .map_err(|_| event_loop_closed())?;
         self.wake_up();
+        Ok(())
     }
 
+    /// Does nothing while the event loop initialization is deferred, queued actions are
+    /// handled as soon as the event loop is built
     pub fn wake_up(&self) {
-        self.event_loop.wake_up();
+        if let Some(backend) = self.backend.get() {
+            backend.proxy.wake_up();
+        }
+    }
+
+    /// Call a host callback on the UI thread, reporting it with a log message and a diagnostics
+    /// event if it takes longer than the callback budget
+    pub fn call_within_budget<R>(
+        &self,
+        kind: WinitCallbackKind,
+        token: usize,
+        window_id: Option<WindowId>,
+        callback: impl FnOnce() -> R,
+    ) -> R {
+        let budget = match self.settings().callback_budget {
+            Some(budget) => budget,
+            None => return callback(),
+        };
+
+        let start = Instant::now();
+        let result = callback();
+        let duration = start.elapsed();
+
+        if duration > budget {
+            warn!(
+                "{:?} callback with token {} took {:?}, over the budget of {:?}",
+                kind, token, duration, budget
+            );
+            let event = WinitCallbackOverBudgetEvent::new(kind, token, duration, budget);
+            self.push_converted_events(window_id, vec![Box::new(event)]);
+        }
+        result
+    }
+
+    /// Enqueue the events that are enabled in the event mask and signal the semaphore,
+    /// device events are not associated with any window
+    fn push_converted_events(&self, window_id: Option<WindowId>, events: Vec<Box<dyn WinitEvent>>) {
+        let enabled_events = self.settings().event_mask;
+        let timestamp = self.timestamp();
+        // also measured for events routed to a sink, which are pushed to the queue of the sink
+        let received_at = Instant::now();
+        let events: Vec<_> = events
+            .into_iter()
+            .filter(|event| event.event_type().mask() & enabled_events != 0)
+            .collect();
+        let has_events = !events.is_empty();
+        let event_mask = events
+            .iter()
+            .fold(0, |mask, event| mask | event.event_type().mask());
+
+        for event in events {
+            self.push_event(WinitWindowEvent {
+                window_id,
+                timestamp,
+                event,
+                enqueued_at: Some(received_at),
+                queue_latency: None,
+            });
+        }
+
+        if has_events {
+            if let Some(semaphore) = &self.semaphore_signaller {
+                semaphore.signal();
+            }
+            if let Some(window_id) = window_id {
+                self.subscriptions.lock().notify(window_id, event_mask);
+            }
+        }
     }
 
     pub fn push_event(&self, event: WinitWindowEvent) {
-        self.events.push_event(event);
+        // the callback may call back into the application handle, it is called without the lock
+        let event_callback = self.event_callback.lock().clone();
+        let event = match event_callback {
+            Some(event_callback) => event_callback.dispatch(event),
+            None => Some(event),
+        };
+
+        let event = event.and_then(|event| self.sinks.lock().dispatch(event));
+        if let Some(event) = event {
+            self.events.push_event(event);
+        }
+    }
+
+    /// Deliver events to the callback as soon as they are received, in addition to or instead
+    /// of the queue depending on the mode
+    pub fn set_event_callback(&self, event_callback: Option<EventCallback>) {
+        *self.event_callback.lock() = event_callback;
+    }
+
+    /// Register an independent consumer of events with its own queue and semaphore
+    pub fn add_event_sink(
+        &self,
+        event_mask: u64,
+        mode: WinitEventSinkMode,
+        semaphore: Option<SemaphoreSignaller>,
+    ) -> EventSinkHandle {
+        self.sinks.lock().add_sink(event_mask, mode, semaphore)
+    }
+
+    pub fn remove_event_sink(&self, sink_id: usize) {
+        self.sinks.lock().remove_sink(sink_id);
     }
 
     pub fn pop_event(&self) -> Option<WinitWindowEvent> {
-        self.events.pop_event()
+        let event = self.events.pop_event();
+        if let Some(event) = &event {
+            self.probe_latency([event]);
+        }
+        event
+    }
+
+    pub fn peek_event(&self) -> Option<(Option<WindowId>, WinitEventType)> {
+        self.events.peek_event()
+    }
+
+    /// Percentiles of the time the latest input events waited in the main queue before the
+    /// host popped them, in nanoseconds
+    pub fn queue_latency(&self) -> WinitLatencyReport {
+        self.events.queue_latency()
+    }
+
+    pub fn pop_events(&self, count: usize) -> VecDeque<WinitWindowEvent> {
+        let events = self.events.pop_events(count);
+        self.probe_latency(&events);
+        events
+    }
+
+    pub fn swap_events(&self) -> EventBatch {
+        let events = self.events.take_events();
+        self.probe_latency(&events);
+        EventBatch::new(events)
+    }
+
+    /// The num lock state as tracked from the keyboard events, see [`crate::track_num_lock`]
+    pub fn num_lock(&self) -> bool {
+        self.num_lock.load(Ordering::Relaxed)
+    }
+
+    /// Must be called from a UI thread for every keyboard event
+    pub fn track_num_lock(&self, event: &KeyEvent, is_synthetic: bool) {
+        let num_lock = track_num_lock(event, is_synthetic, self.num_lock());
+        self.num_lock.store(num_lock, Ordering::Relaxed);
+    }
+
+    /// The modifiers held down as of the latest modifiers changed event, with the num lock
+    /// state tracked from the keyboard events
+    pub fn modifiers(&self) -> WinitEventModifiersChanged {
+        self.modifiers.lock().with_num_lock(self.num_lock())
+    }
+
+    /// Must be called from a UI thread for every modifiers changed event
+    pub fn set_modifiers(&self, modifiers: WinitEventModifiersChanged) {
+        *self.modifiers.lock() = modifiers;
+    }
+
+    /// Approximate heap memory held by the pending events and the windows registry, in bytes
+    pub fn memory_usage(&self) -> usize {
+        self.events.memory_usage() + self.windows_memory_usage.load(Ordering::Relaxed)
+    }
+
+    /// Deliver a host message to the window through the event queue, waking up the event loop
+    pub fn push_user_event(
+        &self,
+        window_id: WindowId,
+        event: WinitUserEvent,
+    ) -> Result<(), WinitError> {
+        self.try_enqueue_action(ApplicationAction::PushUserEvent(PushUserEventAction {
+            window_id,
+            event,
+        }))
+    }
+
+    /// Shrink the event queue right away and the windows registry on the UI thread
+    pub fn trim(&self) {
+        self.events.trim();
+        if let Err(error) = self.try_enqueue_action(ApplicationAction::TrimMemory) {
+            debug!("Could not trim the windows registry: {}", error);
+        }
+    }
+
+    /// A stable integer identifying a device, 0 for events without a device
+    pub fn device_id(&self, device_id: Option<DeviceId>) -> i64 {
+        self.device_ids.lock().get(device_id)
+    }
+
+    pub fn subscribe(
+        &self,
+        window_id: WindowId,
+        event_mask: u64,
+        semaphore: SemaphoreSignaller,
+    ) -> usize {
+        self.subscriptions
+            .lock()
+            .subscribe(window_id, event_mask, semaphore)
+    }
+
+    pub fn unsubscribe(&self, subscription_id: usize) {
+        self.subscriptions.lock().unsubscribe(subscription_id);
+    }
+
+    pub fn set_liveness_notifier(&self, liveness: Option<LivenessNotifier>) {
+        *self.liveness.lock() = liveness;
+    }
+
+    /// Must be called from a UI thread
+    fn notify_alive(&self) {
+        let callback = self
+            .liveness
+            .lock()
+            .as_mut()
+            .and_then(|liveness| liveness.tick());
+        // called without the lock, so that the host can replace the notifier from the callback
+        if let Some(callback) = callback {
+            callback.notify();
+        }
+    }
+
+    fn next_liveness_notification(&self) -> Option<Instant> {
+        self.liveness
+            .lock()
+            .as_ref()
+            .and_then(|liveness| liveness.next_notification())
+    }
+
+    pub fn set_latency_probe(&self, latency_probe: Option<LatencyProbe>) {
+        *self.latency_probe.lock() = latency_probe;
+    }
+
+    fn probe_latency<'a>(&self, events: impl IntoIterator<Item = &'a WinitWindowEvent>) {
+        let report = {
+            let mut latency_probe = self.latency_probe.lock();
+            match latency_probe.as_mut() {
+                None => return,
+                Some(latency_probe) => {
+                    let now = self.timestamp();
+                    events
+                        .into_iter()
+                        .filter_map(|event| latency_probe.record(event, now))
+                        .last()
+                }
+            }
+        };
+
+        if let Some(report) = report {
+            report.notify();
+        }
     }
 
+    /// Unknown until the event loop is initialized
     pub fn get_type(&self) -> WinitEventLoopType {
-        self.event_loop_type
+        self.backend
+            .get()
+            .map(|backend| backend.event_loop_type)
+            .unwrap_or(WinitEventLoopType::Unknown)
+    }
+
+    /// Probed when the event loop is built, none until then
+    pub fn capabilities(&self) -> WinitCapabilities {
+        self.backend
+            .get()
+            .map(|backend| backend.capabilities)
+            .unwrap_or_default()
+    }
+
+    /// The display handle of the event loop, valid for as long as the event loop is alive.
+    /// None until the event loop is initialized
+    pub fn raw_display_handle(&self) -> Option<RawDisplayHandle> {
+        self.backend.get().map(|backend| backend.display_handle.0)
+    }
+
+    /// Let the message hook see the messages sent to the window, see [`Win32MessageHook`]
+    #[cfg(windows_platform)]
+    pub(crate) fn hook_window(&self, window: &dyn winit::window::Window) {
+        if let Some(msg_hook) = &self.msg_hook {
+            msg_hook.hook_window(window);
+        }
+    }
+
+    /// Whether redraws requested by the host are held back until the event loop waits
+    pub fn throttles_redraws(&self) -> bool {
+        self.redraw_throttle.lock().is_enabled()
+    }
+
+    pub fn settings(&self) -> ApplicationSettings {
+        self.settings.lock().clone()
+    }
+
+    pub fn update_settings(&self, f: impl FnOnce(&mut ApplicationSettings)) {
+        f(&mut self.settings.lock());
+    }
+
+    /// Current time of the event clock, used to stamp received events
+    pub fn timestamp(&self) -> u64 {
+        self.clock.lock().now()
+    }
+
+    pub fn set_host_clock(&self, host_clock: Option<HostClock>) {
+        self.clock.lock().set_host_clock(host_clock);
+    }
+
+    /// Keep only the latest cursor position between pops, disabled by default
+    pub fn set_coalesce_cursor_moves(&self, coalesce_cursor_moves: bool) {
+        self.events.set_coalesce_cursor_moves(coalesce_cursor_moves);
+    }
+
+    /// Only enqueue events which type's bit is set in the mask, see [`WinitEventType::mask`].
+    /// Filtered out events are dropped before they are converted whenever possible.
+    pub fn set_event_mask(&self, event_mask: u64) {
+        self.update_settings(|settings| settings.event_mask = event_mask);
+    }
+
+    pub fn set_queue_watchdog(&self, watchdog: Option<EventQueueWatchdog>) {
+        self.events.set_watchdog(watchdog);
     }
 }
 
 #[derive(Debug)]
 pub struct RunningApplication {
     receiver: Receiver<ApplicationAction>,
+    priority_receiver: Receiver<ApplicationAction>,
+    /// Actions received before the event loop was initialized
+    pending_actions: Vec<ApplicationAction>,
     windows: Mutex<HashMap<WindowId, WindowHandle>>,
     application_handle: ApplicationHandle,
-    semaphore_signaller: Option<SemaphoreSignaller>,
     wakeup_signallers: Mutex<Vec<WakeUpSignaller>>,
+    listens_unfocused_device_events: bool,
+    /// Native ids of recreated windows, mapped to the id the host knows the window by
+    native_window_ids: HashMap<WindowId, WindowId>,
+    /// Native windows that were replaced and whose events are ignored
+    lost_window_ids: HashSet<WindowId>,
+    /// The monitors found by the last check, None until the first check
+    monitor_areas: Option<Vec<ScreenArea>>,
+    last_monitor_check: Instant,
 }
 
 impl RunningApplication {
     pub fn enqueue_event(&mut self, event: WindowEvent, window_id: WindowId) {
         if let Some(window) = self.windows.lock().get(&window_id) {
+            let enabled_events = self.application_handle.settings().event_mask;
+            if window_event_mask(&event) & enabled_events == 0 {
+                return;
+            }
+
             let events = convert_event(event, window);
-            let has_events = !events.is_empty();
+            self.push_window_events(window_id, events);
+        }
+    }
 
-            for event in events {
-                self.application_handle
-                    .push_event(WinitWindowEvent { window_id, event });
-            }
+    fn push_window_events(&self, window_id: WindowId, events: Vec<Box<dyn WinitEvent>>) {
+        self.application_handle
+            .push_converted_events(Some(window_id), events);
+    }
 
-            if has_events {
-                if let Some(semaphore) = &self.semaphore_signaller {
-                    semaphore.signal();
-                }
+    /// The host learns that an action addressed to a window was dropped
+    fn enqueue_window_closed(&self, action: WinitActionType, window_id: WindowId) {
+        let event =
+            WinitActionFailedEvent::new(action, window_id, WinitActionFailure::WindowClosed);
+        self.push_window_events(window_id, vec![Box::new(event)]);
+    }
+
+    fn enqueue_window_recreated(&self, window_handle: &WindowHandle) {
+        let raw_handles = window_handle.raw_window_handle().and_then(|window| {
+            window_handle
+                .raw_display_handle()
+                .map(|display| (window, display))
+        });
+
+        match raw_handles {
+            Ok((window, display)) => {
+                let event = WinitWindowRecreatedEvent::new(
+                    VeryRawWindowHandle::from(window),
+                    VeryRawDisplayHandle::from(display),
+                    window_handle.scale_factor(),
+                    window_handle.surface_size(),
+                );
+                self.push_window_events(window_handle.id(), vec![Box::new(event)]);
             }
+            Err(error) => error!("Recreated window has no raw handles: {}", error),
+        }
+    }
+
+    pub fn enqueue_device_event(&mut self, event: DeviceEvent, device_id: Option<DeviceId>) {
+        let events = convert_device_event(device_id, event, &self.application_handle);
+        self.application_handle.push_converted_events(None, events);
+    }
+
+    /// Raw device input must be delivered even when the application is not focused
+    fn update_device_events(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let raw_device_input = self.application_handle.settings().raw_device_input;
+        if raw_device_input != self.listens_unfocused_device_events {
+            self.listens_unfocused_device_events = raw_device_input;
+            event_loop.listen_device_events(if raw_device_input {
+                DeviceEvents::Always
+            } else {
+                DeviceEvents::WhenFocused
+            });
         }
     }
 
     fn handle_action(&mut self, event_loop: &dyn ActiveEventLoop, action: ApplicationAction) {
+        trace_span!("handle_action", action = ?action);
         match action {
             ApplicationAction::FunctionCall(action) => {
-                unsafe { (action.callback)(action.thunk) };
+                self.application_handle.call_within_budget(
+                    WinitCallbackKind::FunctionCall,
+                    action.token,
+                    None,
+                    || action.call(),
+                );
             }
             ApplicationAction::CreateWindow(action) => {
-                if let Ok(window) = event_loop.create_window(action.window_attributes) {
-                    window.set_ime_allowed(true);
-
-                    let window_handle = WindowHandle::for_window(&self.application_handle, window);
-                    self.windows
-                        .lock()
-                        .insert(window_handle.id(), window_handle.clone());
-                    (action.callback)(window_handle);
+                let window_attributes = action.window_attributes.resolve(event_loop);
+                match event_loop.create_window(window_attributes.clone()) {
+                    Ok(window) => {
+                        window.set_ime_allowed(true);
+                        #[cfg(windows_platform)]
+                        self.application_handle.hook_window(window.as_ref());
+
+                        let window_handle = WindowHandle::for_window(
+                            &self.application_handle,
+                            window,
+                            window_attributes,
+                        );
+                        let listener_ids = window_handle.add_listeners(action.listeners);
+                        self.windows
+                            .lock()
+                            .insert(window_handle.id(), window_handle.clone());
+                        if action.focus {
+                            window_handle.focus_window();
+                        }
+                        let initial_state = window_handle.initial_state();
+                        (action.callback)(window_handle, initial_state, listener_ids);
+                    }
+                    Err(error) => {
+                        let code = WinitError::from(error).report();
+                        if let Some(error_callback) = action.error_callback {
+                            error_callback(code);
+                        }
+                    }
                 }
             }
             ApplicationAction::RequestWindowSurfaceSize(action) => {
-                if let Some(handle) = self.windows.lock().get(&action.window_id) {
-                    if let Some(window) = handle.window.lock().as_ref() {
-                        let _ = window.request_surface_size(action.surface_size);
+                let is_requested = match self.windows.lock().get(&action.window_id) {
+                    Some(handle) => match handle.window.lock().as_ref() {
+                        Some(window) => {
+                            handle.expect_resize(WinitResizeCause::Program, action.surface_size);
+                            let _ = window.request_surface_size(action.surface_size);
+                            true
+                        }
+                        None => false,
+                    },
+                    None => false,
+                };
+                if !is_requested {
+                    self.enqueue_window_closed(
+                        WinitActionType::RequestWindowSurfaceSize,
+                        action.window_id,
+                    );
+                }
+            }
+            ApplicationAction::ApplyWindowConfig(action) => {
+                let window_handle = self.windows.lock().get(&action.window_id).cloned();
+                match window_handle {
+                    Some(window_handle) => window_handle.apply_config(action.config),
+                    None => self.enqueue_window_closed(
+                        WinitActionType::ApplyWindowConfig,
+                        action.window_id,
+                    ),
+                }
+            }
+            ApplicationAction::FocusWindow(action) => {
+                // focusing may deliver window events right away, which look the window up
+                let window_handle = self.windows.lock().get(&action.window_id).cloned();
+                match window_handle {
+                    Some(window_handle) => window_handle.focus_window(),
+                    None => {
+                        self.enqueue_window_closed(WinitActionType::FocusWindow, action.window_id)
                     }
                 }
             }
+            ApplicationAction::RecreateWindow(action) => {
+                let window_handle = self.windows.lock().get(&action.window_id).cloned();
+                if let Some(window_handle) = window_handle {
+                    let lost_id = window_handle.native_id();
+                    match window_handle.recreate(event_loop) {
+                        Ok(native_id) => {
+                            if let Some(lost_id) = lost_id {
+                                self.native_window_ids.remove(&lost_id);
+                                self.lost_window_ids.insert(lost_id);
+                            }
+                            self.native_window_ids.insert(native_id, window_handle.id());
+                            self.enqueue_window_recreated(&window_handle);
+                        }
+                        Err(error) => {
+                            WinitError::from(error).report();
+                        }
+                    }
+                } else {
+                    self.enqueue_window_closed(WinitActionType::RecreateWindow, action.window_id);
+                }
+            }
+            #[cfg(feature = "test-harness")]
+            ApplicationAction::InjectWindowEvent(action) => {
+                // the event arrives through the native id, like the ones sent by the platform
+                let native_id = self
+                    .windows
+                    .lock()
+                    .get(&action.window_id)
+                    .and_then(|window_handle| window_handle.native_id());
+                if let Some(native_id) = native_id {
+                    self.window_event(event_loop, native_id, action.event);
+                }
+            }
+            ApplicationAction::Acknowledged(action) => {
+                self.handle_action(event_loop, *action.action);
+                action.acknowledgement.acknowledge();
+            }
+            ApplicationAction::PushUserEvent(action) => {
+                self.push_window_events(action.window_id, vec![Box::new(action.event)]);
+            }
+            ApplicationAction::TrimMemory => {
+                self.windows.lock().shrink_to_fit();
+                self.native_window_ids.shrink_to_fit();
+                self.lost_window_ids.shrink_to_fit();
+            }
+        }
+        self.publish_memory_usage();
+    }
+
+    fn publish_memory_usage(&self) {
+        let windows =
+            self.windows.lock().capacity() * std::mem::size_of::<(WindowId, WindowHandle)>();
+        let window_ids = self.native_window_ids.capacity()
+            * std::mem::size_of::<(WindowId, WindowId)>()
+            + self.lost_window_ids.capacity() * std::mem::size_of::<WindowId>();
+        self.application_handle
+            .windows_memory_usage
+            .store(windows + window_ids, Ordering::Relaxed);
+    }
+
+    fn handle_pending_actions(&mut self, event_loop: &dyn ActiveEventLoop) {
+        for action in std::mem::take(&mut self.pending_actions) {
+            self.handle_action(event_loop, action);
+        }
+    }
+
+    /// Request the redraws held back by the throttle once the minimum idle time is over.
+    /// Returns until when the event loop should wait, without blocking the UI thread
+    fn throttle_redraws(&mut self) -> Option<Instant> {
+        let windows: Vec<_> = self
+            .windows
+            .lock()
+            .values()
+            .filter(|window_handle| window_handle.has_deferred_redraw())
+            .cloned()
+            .collect();
+        if windows.is_empty() {
+            return None;
+        }
+
+        let deadline = self
+            .application_handle
+            .redraw_throttle
+            .lock()
+            .try_release(Instant::now());
+        if deadline.is_none() {
+            for window_handle in windows {
+                window_handle.request_deferred_redraw();
+            }
+        }
+        deadline
+    }
+
+    /// Move the windows that ended up entirely off-screen after the monitors changed, for
+    /// example when a display was unplugged, back onto the nearest monitor.
+    /// There is no event for monitor changes, so the monitors are compared periodically.
+    /// Returns when the monitors should be checked next, None while disabled
+    fn reposition_offscreen_windows(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+    ) -> Option<Instant> {
+        if !self
+            .application_handle
+            .settings()
+            .reposition_offscreen_windows
+        {
+            self.monitor_areas = None;
+            return None;
+        }
+        let next_check = self.last_monitor_check + MONITOR_CHECK_INTERVAL;
+        if Instant::now() < next_check {
+            return Some(next_check);
+        }
+        self.last_monitor_check = Instant::now();
+        let next_check = self.last_monitor_check + MONITOR_CHECK_INTERVAL;
+
+        let monitor_areas = ScreenArea::all(event_loop);
+        let previous_monitor_areas = self.monitor_areas.replace(monitor_areas.clone());
+        if previous_monitor_areas.map_or(true, |previous| previous == monitor_areas) {
+            return Some(next_check);
+        }
+
+        let windows: Vec<_> = self
+            .windows
+            .lock()
+            .iter()
+            .map(|(window_id, window_handle)| (*window_id, window_handle.clone()))
+            .collect();
+        for (window_id, window_handle) in windows {
+            // (Windows) minimized windows are parked far off-screen
+            if window_handle.is_minimized() == Some(true) {
+                continue;
+            }
+            let moved = window_handle.outer_area().and_then(|window_area| {
+                offscreen_window_position(&window_area, &monitor_areas)
+                    .map(|position| (window_area.position, position))
+            });
+            if let Some((from, to)) = moved {
+                window_handle.set_outer_position(Position::Physical(to));
+                let event = WinitWindowRepositionedEvent::new(from, to);
+                self.push_window_events(window_id, vec![Box::new(event)]);
+            }
         }
+
+        Some(next_check)
     }
 
     fn signal_wakeup(&self) {
@@ -207,14 +1091,18 @@ impl RunningApplication {
 }
 
 impl ApplicationHandler for RunningApplication {
-    fn can_create_surfaces(&mut self, _event_loop: &dyn ActiveEventLoop) {
+    fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
         info!("Application is able to create a surfaces now");
+        self.handle_pending_actions(event_loop);
     }
 
     fn proxy_wake_up(&mut self, event_loop: &dyn ActiveEventLoop) {
-        while let Ok(action) = self.receiver.try_recv() {
-            self.handle_action(event_loop, action)
+        self.application_handle.notify_alive();
+        self.handle_pending_actions(event_loop);
+        while let Some(action) = next_action(&self.priority_receiver, &self.receiver) {
+            self.handle_action(event_loop, action);
         }
+        self.update_device_events(event_loop);
         self.signal_wakeup();
     }
 
@@ -224,36 +1112,181 @@ impl ApplicationHandler for RunningApplication {
         window_id: WindowId,
         event: WindowEvent,
     ) {
+        self.application_handle.notify_alive();
+
+        // events of native windows replaced by recreation are no longer delivered
+        if self.lost_window_ids.contains(&window_id) {
+            if matches!(event, WindowEvent::Destroyed) {
+                self.lost_window_ids.remove(&window_id);
+            }
+            return;
+        }
+        // recreated windows keep the id of the window they replace
+        let window_id = self
+            .native_window_ids
+            .get(&window_id)
+            .copied()
+            .unwrap_or(window_id);
+
         match &event {
+            // the windows map is not locked while host listeners are called
             WindowEvent::SurfaceResized(size) => {
-                if let Some(window_handle) = self.windows.lock().get(&window_id) {
+                let window_handle = self.windows.lock().get(&window_id).cloned();
+                if let Some(window_handle) = window_handle {
                     window_handle.on_window_resized(size);
                 }
             }
+            // tracked even when keyboard events are disabled in the event mask
+            WindowEvent::KeyboardInput {
+                event,
+                is_synthetic,
+                ..
+            } => {
+                self.application_handle.track_num_lock(event, *is_synthetic);
+                if let Some(window_handle) = self.windows.lock().get(&window_id) {
+                    window_handle.on_key_input(event);
+                }
+            }
+            WindowEvent::Focused(is_focused) => {
+                let window_handle = self.windows.lock().get(&window_id).cloned();
+                if let Some(window_handle) = window_handle {
+                    if !is_focused {
+                        window_handle.release_pressed_keys();
+                    }
+                    window_handle.on_window_focused(*is_focused);
+                }
+            }
+            WindowEvent::CloseRequested => {
+                let window_handle = self.windows.lock().get(&window_id).cloned();
+                if let Some(window_handle) = window_handle {
+                    window_handle.on_close_requested();
+                }
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                let window_handle = self.windows.lock().get(&window_id).cloned();
+                if let Some(window_handle) = window_handle {
+                    let size = window_handle.scaled_surface_size(*scale_factor);
+                    window_handle.on_scale_factor_changed(*scale_factor, &size);
+                }
+            }
             WindowEvent::Moved(position) => {
                 if let Some(window_handle) = self.windows.lock().get(&window_id) {
                     window_handle.on_window_moved(position);
                 }
             }
-            WindowEvent::RedrawRequested => {
+            // wheel events come without a position, they happen where the pointer was last seen
+            WindowEvent::PointerMoved { position, .. }
+            | WindowEvent::PointerEntered { position, .. }
+            | WindowEvent::PointerButton { position, .. } => {
                 if let Some(window_handle) = self.windows.lock().get(&window_id) {
+                    window_handle.on_pointer_moved(position);
+                }
+            }
+            WindowEvent::RedrawRequested => {
+                let window_handle = self.windows.lock().get(&window_id).cloned();
+                if let Some(window_handle) = window_handle {
                     window_handle.on_window_redraw();
                 }
             }
             _ => {}
         }
+        let is_destroyed = matches!(event, WindowEvent::Destroyed);
+        let may_change_state = matches!(
+            event,
+            WindowEvent::SurfaceResized(_) | WindowEvent::Occluded(_)
+        );
+        let may_change_monitor = matches!(
+            event,
+            WindowEvent::Moved(_) | WindowEvent::ScaleFactorChanged { .. }
+        );
         self.enqueue_event(event, window_id);
-    }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
-pub enum WinitEventLoopType {
+        if may_change_state {
+            let window_handle = self.windows.lock().get(&window_id).cloned();
+            let state_change =
+                window_handle.and_then(|window_handle| window_handle.update_window_state());
+            if let Some((previous_state, state)) = state_change {
+                let event = WinitWindowStateChangedEvent::new(previous_state, state);
+                self.push_window_events(window_id, vec![Box::new(event)]);
+            }
+        }
+
+        if may_change_monitor {
+            let window_handle = self.windows.lock().get(&window_id).cloned();
+            let monitor = window_handle.and_then(|window_handle| window_handle.update_monitor());
+            if let Some(monitor) = monitor {
+                let event = WinitWindowMonitorChangedEvent::new(&monitor);
+                self.push_window_events(window_id, vec![Box::new(event)]);
+            }
+        }
+
+        // the window is gone, the host got notified and we no longer need to keep it around
+        if is_destroyed {
+            self.windows.lock().remove(&window_id);
+            self.native_window_ids.retain(|_, id| *id != window_id);
+            self.application_handle
+                .subscriptions
+                .lock()
+                .unsubscribe_window(window_id);
+            self.publish_memory_usage();
+        }
+    }
+
+    fn device_event(
+        &mut self,
+        _event_loop: &dyn ActiveEventLoop,
+        device_id: Option<DeviceId>,
+        event: DeviceEvent,
+    ) {
+        self.application_handle.notify_alive();
+        self.enqueue_device_event(event, device_id);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.application_handle.notify_alive();
+        let next_monitor_check = self.reposition_offscreen_windows(event_loop);
+        if self.application_handle.settings().follow_ime_caret {
+            for window_handle in self.windows.lock().values() {
+                window_handle.apply_ime_caret_area();
+            }
+        }
+        let redraw_deadline = self.throttle_redraws();
+
+        // periodic checks only run from here, wake up in time for the next one
+        let deadline = [
+            redraw_deadline,
+            next_monitor_check,
+            self.application_handle.next_liveness_notification(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        event_loop.set_control_flow(match deadline {
+            Some(deadline) => ControlFlow::WaitUntil(deadline),
+            None => ControlFlow::Wait,
+        });
+    }
+}
+
+/// The display connection is owned by the event loop and outlives the application handle,
+/// the pointers are only ever handed over to the host and never dereferenced by us
+#[derive(Debug, Clone, Copy)]
+struct EventLoopDisplayHandle(RawDisplayHandle);
+
+unsafe impl Send for EventLoopDisplayHandle {}
+unsafe impl Sync for EventLoopDisplayHandle {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitEventLoopType {
     Windows,
     MacOS,
     X11,
     Wayland,
     Unknown,
+    Android,
+    IOS,
+    Web,
 }
 
 impl From<RawDisplayHandle> for WinitEventLoopType {
@@ -263,6 +1296,9 @@ impl From<RawDisplayHandle> for WinitEventLoopType {
             RawDisplayHandle::Xlib(_) => Self::X11,
             RawDisplayHandle::Wayland(_) => Self::Wayland,
             RawDisplayHandle::Windows(_) => Self::Windows,
+            RawDisplayHandle::Android(_) => Self::Android,
+            RawDisplayHandle::UiKit(_) => Self::IOS,
+            RawDisplayHandle::Web(_) => Self::Web,
             _ => Self::Unknown,
         }
     }
@@ -302,6 +1338,80 @@ pub extern "C" fn winit_application_builder_with_android_app(
         .log();
 }
 
+/// Register a message hook receiving (thunk, hwnd, msg, wparam, lparam) for the messages
+/// posted to the UI thread, such as `WM_HOTKEY`, and for the messages posted or sent to its
+/// windows, such as `WM_COPYDATA` or custom shell messages. The hwnd is null for the messages
+/// of the thread. Returning true from the callback marks the message as handled so that
+/// winit does not see it, the window procedure then returns 0.
+/// The callback is called from the UI thread.
+#[cfg(windows_platform)]
+#[no_mangle]
+pub extern "C" fn winit_application_builder_with_msg_hook(
+    application_builder: *mut ValueBox<ApplicationBuilder>,
+    callback: unsafe extern "C" fn(*const c_void, *mut c_void, u32, usize, isize) -> bool,
+    thunk: *const c_void,
+) {
+    application_builder
+        .with_mut_ok(|application_builder| {
+            application_builder.with_msg_hook(Win32MessageHook::new(callback, thunk));
+        })
+        .log();
+}
+
+/// Enforce a minimum time between the redraws requested by the host while windows are redrawn
+/// continuously, in microseconds. Zero disables the throttling.
+#[no_mangle]
+pub extern "C" fn winit_application_builder_set_minimum_idle_sleep(
+    application_builder: *mut ValueBox<ApplicationBuilder>,
+    minimum_idle_sleep_micros: u64,
+) {
+    application_builder
+        .with_mut_ok(|application_builder| {
+            application_builder
+                .set_minimum_idle_sleep(Duration::from_micros(minimum_idle_sleep_micros));
+        })
+        .log();
+}
+
+/// Defer the connection to the windowing system until the first window is requested.
+/// Until then the event loop type is unknown and function calls are executed directly
+/// on the thread that runs the application.
+#[no_mangle]
+pub extern "C" fn winit_application_builder_set_lazy_initialization(
+    application_builder: *mut ValueBox<ApplicationBuilder>,
+    lazy_initialization: bool,
+) {
+    application_builder
+        .with_mut_ok(|application_builder| {
+            application_builder.set_lazy_initialization(lazy_initialization);
+        })
+        .log();
+}
+
+/// Name the thread that will run the event loop, applied when the application starts running
+#[no_mangle]
+pub extern "C" fn winit_application_builder_set_thread_name(
+    application_builder: *mut ValueBox<ApplicationBuilder>,
+    name: *mut ValueBox<StringBox>,
+) {
+    application_builder
+        .with_mut(|application_builder| {
+            name.with_ref_ok(|name| application_builder.set_thread_name(name.to_string()))
+        })
+        .log();
+}
+
+/// Change the priority of the thread that will run the event loop, where supported
+#[no_mangle]
+pub extern "C" fn winit_application_builder_set_thread_priority(
+    application_builder: *mut ValueBox<ApplicationBuilder>,
+    priority: WinitThreadPriority,
+) {
+    application_builder
+        .with_mut_ok(|application_builder| application_builder.set_thread_priority(priority))
+        .log();
+}
+
 #[no_mangle]
 pub extern "C" fn winit_application_builder_set_semaphore_signaller(
     application_builder: *mut ValueBox<ApplicationBuilder>,
@@ -357,10 +1467,28 @@ pub extern "C" fn winit_application_call_function(
     let application_handle = application_handle as *mut ValueBox<ApplicationHandle>;
     application_handle
         .with_ref_ok(|application_handle| {
-            application_handle.enqueue_action(ApplicationAction::FunctionCall(FunctionCallAction {
-                callback,
-                thunk,
-            }))
+            application_handle.enqueue_action(ApplicationAction::FunctionCall(
+                FunctionCallAction::new(callback, thunk),
+            ))
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Same as [`winit_application_call_function`], but the call overtakes the queued normal
+/// actions, for example to update the cursor or the focus while background calls are queued
+#[no_mangle]
+pub extern "C" fn winit_application_call_function_with_priority(
+    application_handle: *const c_void,
+    callback: extern "C" fn(*const c_void),
+    thunk: *const c_void,
+) -> bool {
+    let application_handle = application_handle as *mut ValueBox<ApplicationHandle>;
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.enqueue_priority_action(ApplicationAction::FunctionCall(
+                FunctionCallAction::new(callback, thunk),
+            ))
         })
         .map(|_| true)
         .or_log(false)
@@ -391,27 +1519,224 @@ pub extern "C" fn winit_application_release(application: *mut ValueBox<Applicati
     application.release();
 }
 
+/// Request a window to be created on the UI thread. The semaphore is signalled once the
+/// request is handled, with the window handle set to null if the window could not be created,
+/// see [`crate::winit_last_error_get_code`].
 #[no_mangle]
 pub extern "C" fn winit_application_handle_create_window(
     application_handle: *mut ValueBox<ApplicationHandle>,
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
     window_handle: *mut *mut ValueBox<WindowHandle>,
-) {
+) -> WinitErrorCode {
+    create_window(
+        application_handle,
+        window_attributes,
+        semaphore_signaller,
+        window_handle,
+        std::ptr::null_mut(),
+        false,
+        Default::default(),
+        vec![],
+    )
+}
+
+/// Same as [`winit_application_handle_create_window`], but also writes the initial scale factor,
+/// position and surface size of the created window before signalling the semaphore.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_create_window_with_state(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
+    window_handle: *mut *mut ValueBox<WindowHandle>,
+    initial_state: *mut WinitWindowInitialState,
+) -> WinitErrorCode {
+    create_window(
+        application_handle,
+        window_attributes,
+        semaphore_signaller,
+        window_handle,
+        initial_state,
+        false,
+        Default::default(),
+        vec![],
+    )
+}
+
+/// Same as [`winit_application_handle_create_window`], but the redraw and resize listeners
+/// are registered before the window receives any event, so the very first resize or redraw
+/// is never missed. Either callback may be null.
+/// The ids of the listeners, to pass to [`crate::winit_window_handle_remove_listener`], are
+/// written together with the window handle, 0 for a null callback. The id pointers may be null.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn winit_application_handle_create_window_with_listeners(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    redraw_callback: Option<unsafe extern "C" fn(*const c_void)>,
+    redraw_thunk: *const c_void,
+    resize_callback: Option<unsafe extern "C" fn(*const c_void, u32, u32)>,
+    resize_thunk: *const c_void,
+    semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
+    window_handle: *mut *mut ValueBox<WindowHandle>,
+    redraw_listener_id: *mut usize,
+    resize_listener_id: *mut usize,
+) -> WinitErrorCode {
+    let mut listeners = WindowListeners::default();
+    let mut listener_id_slots = vec![];
+    for slot in [redraw_listener_id, resize_listener_id] {
+        if !slot.is_null() {
+            unsafe { *slot = 0 };
+        }
+    }
+    if let Some(callback) = redraw_callback {
+        listeners
+            .redraw
+            .push(WindowRedrawRequestedListener::new(callback, redraw_thunk));
+        listener_id_slots.push(redraw_listener_id);
+    }
+    if let Some(callback) = resize_callback {
+        listeners
+            .resize
+            .push(WindowResizedListener::new(callback, resize_thunk));
+        listener_id_slots.push(resize_listener_id);
+    }
+
+    create_window(
+        application_handle,
+        window_attributes,
+        semaphore_signaller,
+        window_handle,
+        std::ptr::null_mut(),
+        false,
+        listeners,
+        listener_id_slots,
+    )
+}
+
+/// Same as [`winit_application_handle_create_window`], but the window is focused right after
+/// it is created, within the same action on the UI thread.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_create_focused_window(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
+    window_handle: *mut *mut ValueBox<WindowHandle>,
+) -> WinitErrorCode {
+    create_window(
+        application_handle,
+        window_attributes,
+        semaphore_signaller,
+        window_handle,
+        std::ptr::null_mut(),
+        true,
+        Default::default(),
+        vec![],
+    )
+}
+
+/// Where the UI thread writes the created window for the host, which keeps the memory alive
+/// until the semaphore is signalled
+struct CreatedWindowSlots {
+    semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
+    window_handle: *mut *mut ValueBox<WindowHandle>,
+    initial_state: *mut WinitWindowInitialState,
+    listener_ids: Vec<*mut usize>,
+}
+
+unsafe impl Send for CreatedWindowSlots {}
+
+impl CreatedWindowSlots {
+    fn created(
+        self,
+        window: WindowHandle,
+        state: WinitWindowInitialState,
+        listener_ids: Vec<usize>,
+    ) {
+        unsafe {
+            *self.window_handle = value_box!(window).into_raw();
+            if !self.initial_state.is_null() {
+                *self.initial_state = state;
+            }
+            for (slot, id) in self.listener_ids.iter().zip(listener_ids) {
+                if !slot.is_null() {
+                    **slot = id;
+                }
+            }
+        };
+        self.signal();
+    }
+
+    fn failed(self) {
+        unsafe { *self.window_handle = std::ptr::null_mut() };
+        self.signal();
+    }
+
+    fn signal(&self) {
+        self.semaphore_signaller
+            .with_ref_ok(|signaller| {
+                signaller.signal();
+            })
+            .log();
+    }
+}
+
+fn event_loop_closed() -> WinitError {
+    WinitError::new(
+        WinitErrorCode::EventLoopClosed,
+        "The event loop is no longer running",
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_window(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    semaphore_signaller: *mut ValueBox<SemaphoreSignaller>,
+    window_handle: *mut *mut ValueBox<WindowHandle>,
+    initial_state: *mut WinitWindowInitialState,
+    focus: bool,
+    listeners: WindowListeners,
+    listener_id_slots: Vec<*mut usize>,
+) -> WinitErrorCode {
+    // only one of the callbacks is ever called
+    let slots = Arc::new(Mutex::new(Some(CreatedWindowSlots {
+        semaphore_signaller,
+        window_handle,
+        initial_state,
+        listener_ids: listener_id_slots,
+    })));
+    let error_slots = slots.clone();
+
     application_handle
         .with_ref(|application_handle| {
             window_attributes.take_value().map(|window_attributes| {
-                application_handle.create_window(window_attributes, move |window| {
-                    unsafe { *window_handle = value_box!(window).into_raw() };
-                    semaphore_signaller
-                        .with_ref_ok(|signaller| {
-                            signaller.signal();
-                        })
-                        .log();
-                })
+                let callback = move |window: WindowHandle,
+                                     state: WinitWindowInitialState,
+                                     listener_ids: Vec<usize>| {
+                    if let Some(slots) = slots.lock().take() {
+                        slots.created(window, state, listener_ids);
+                    }
+                };
+                let error_callback = move |_code: WinitErrorCode| {
+                    if let Some(slots) = error_slots.lock().take() {
+                        slots.failed();
+                    }
+                };
+                application_handle.try_enqueue_action(ApplicationAction::CreateWindow(
+                    CreateWindowAction {
+                        window_attributes,
+                        focus,
+                        listeners,
+                        callback: Box::new(callback),
+                        error_callback: Some(Box::new(error_callback)),
+                    },
+                ))
             })
         })
-        .log();
+        .map_err(WinitError::from)
+        .and_then(|result| result)
+        .into_error_code()
 }
 
 #[no_mangle]
@@ -420,6 +1745,41 @@ pub extern "C" fn winit_application_handle_pop_event(
     window_id: *mut usize,
     event_type: *mut WinitEventType,
     event_ptr: *mut *mut c_void,
+) -> *mut ValueBox<WinitWindowEvent> {
+    pop_event(
+        application_handle,
+        window_id,
+        event_type,
+        event_ptr,
+        std::ptr::null_mut(),
+    )
+}
+
+/// Same as [`winit_application_handle_pop_event`], but also writes the timestamp of the event,
+/// by default nanoseconds since the application start.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_pop_event_with_timestamp(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_id: *mut usize,
+    event_type: *mut WinitEventType,
+    event_ptr: *mut *mut c_void,
+    timestamp: *mut u64,
+) -> *mut ValueBox<WinitWindowEvent> {
+    pop_event(
+        application_handle,
+        window_id,
+        event_type,
+        event_ptr,
+        timestamp,
+    )
+}
+
+fn pop_event(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_id: *mut usize,
+    event_type: *mut WinitEventType,
+    event_ptr: *mut *mut c_void,
+    timestamp: *mut u64,
 ) -> *mut ValueBox<WinitWindowEvent> {
     application_handle
         .with_ref_ok(|application_handle| {
@@ -429,9 +1789,15 @@ pub extern "C" fn winit_application_handle_pop_event(
                     debug!("Pop window event: {:?}", &window_event);
 
                     unsafe {
-                        *window_id = window_event.window_id().into_raw();
+                        *window_id = window_event
+                            .window_id()
+                            .map(|id| id.into_raw())
+                            .unwrap_or(0);
                         *event_type = window_event.event_type();
                         *event_ptr = window_event.as_ptr();
+                        if !timestamp.is_null() {
+                            *timestamp = window_event.timestamp();
+                        }
                     };
                     value_box!(window_event).into_raw()
                 })
@@ -440,6 +1806,606 @@ pub extern "C" fn winit_application_handle_pop_event(
         .or_log(std::ptr::null_mut())
 }
 
+/// Register an event sink with its own queue receiving events of the types in the mask,
+/// either as copies or instead of the main queue depending on the mode.
+/// The semaphore is optional and is owned by the sink. The returned sink must be released,
+/// it is null for an unknown mode.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_add_event_sink(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    event_mask: u64,
+    mode: u8,
+    semaphore: *mut ValueBox<SemaphoreSignaller>,
+) -> *mut ValueBox<EventSinkHandle> {
+    let semaphore = if semaphore.is_null() {
+        None
+    } else {
+        semaphore.take_value().ok()
+    };
+    let mode = match WinitEventSinkMode::try_from(mode) {
+        Ok(mode) => mode,
+        Err(error) => {
+            error.report();
+            return std::ptr::null_mut();
+        }
+    };
+
+    application_handle
+        .with_ref_ok(|application_handle| {
+            value_box!(application_handle.add_event_sink(event_mask, mode, semaphore)).into_raw()
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+/// Register a callback `(window_id, event_type, event_ptr, thunk)` called from the UI thread
+/// with every event as soon as it is received. Routed events are not enqueued, observed ones
+/// are also enqueued. The event pointer is only valid during the call.
+/// Passing a null callback removes the registered one.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_event_callback(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    callback: Option<unsafe extern "C" fn(usize, WinitEventType, *mut c_void, *const c_void)>,
+    thunk: *const c_void,
+    mode: u8,
+) -> WinitErrorCode {
+    WinitEventSinkMode::try_from(mode)
+        .and_then(|mode| {
+            application_handle
+                .with_ref_ok(|application_handle| {
+                    application_handle.set_event_callback(
+                        callback.map(|callback| EventCallback::new(callback, thunk, mode)),
+                    )
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+/// Stop delivering events to the sink, the sink itself must still be released
+#[no_mangle]
+pub extern "C" fn winit_application_handle_remove_event_sink(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    sink: *mut ValueBox<EventSinkHandle>,
+) {
+    application_handle
+        .with_ref(|application_handle| {
+            sink.with_ref_ok(|sink| application_handle.remove_event_sink(sink.id()))
+        })
+        .log();
+}
+
+/// Report the window id and type of the next event without removing it from the queue.
+/// Returns false if the queue is empty.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_peek_event(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_id: *mut usize,
+    event_type: *mut WinitEventType,
+) -> bool {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .peek_event()
+                .map(|(next_window_id, next_event_type)| {
+                    unsafe {
+                        *window_id = next_window_id.map(|id| id.into_raw()).unwrap_or(0);
+                        *event_type = next_event_type;
+                    };
+                    true
+                })
+                .unwrap_or(false)
+        })
+        .or_log(false)
+}
+
+/// Pop up to `capacity` events into a buffer provided by the host, with a single lock of the
+/// queue. Returns the amount of written records, each record owns its event box.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_pop_events(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    out_buffer: *mut WinitEventRecord,
+    capacity: usize,
+) -> usize {
+    if out_buffer.is_null() || capacity == 0 {
+        return 0;
+    }
+
+    application_handle
+        .with_ref_ok(|application_handle| {
+            let events = application_handle.pop_events(capacity);
+            let count = events.len();
+            for (index, event) in events.into_iter().enumerate() {
+                unsafe { out_buffer.add(index).write(WinitEventRecord::from(event)) };
+            }
+            count
+        })
+        .or_log(0)
+}
+
+/// Choose which scroll axes have their delta sign inverted in mouse wheel events.
+/// By default only the horizontal axis is inverted.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_scroll_inversion(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    invert_x: bool,
+    invert_y: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.scroll.invert_x = invert_x;
+                settings.scroll.invert_y = invert_y;
+            })
+        })
+        .log();
+}
+
+/// Convert line scroll deltas to pixel deltas with the given amount of pixels per line.
+/// A value that is not positive keeps the line deltas, which is the default.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_scroll_pixels_per_line(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    pixels_per_line: f64,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.scroll.pixels_per_line = if pixels_per_line > 0.0 {
+                    Some(pixels_per_line)
+                } else {
+                    None
+                };
+            })
+        })
+        .log();
+}
+
+/// Annotate mouse button events with the number of the click in a series of quick clicks.
+/// Presses of the same button count as one series while each follows the previous one within
+/// the interval and the distance in physical pixels. Disabled by default.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_click_counting(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    is_enabled: bool,
+    interval_millis: u64,
+    distance: f64,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.clicks.is_enabled = is_enabled;
+                settings.clicks.interval = Duration::from_millis(interval_millis);
+                settings.clicks.distance = distance;
+            })
+        })
+        .log();
+}
+
+/// Move windows that are entirely off-screen after the monitors change back onto the nearest
+/// monitor, announced by repositioned events. Disabled by default.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_reposition_offscreen_windows(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    reposition_offscreen_windows: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.reposition_offscreen_windows = reposition_offscreen_windows;
+            })
+        })
+        .log();
+}
+
+/// Report host callbacks that block the UI thread for longer than the budget in microseconds
+/// with a log message and a diagnostics event. A budget of 0 stops measuring callbacks, which
+/// is the default.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_callback_budget(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    budget_micros: u64,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.callback_budget = if budget_micros > 0 {
+                    Some(Duration::from_micros(budget_micros))
+                } else {
+                    None
+                };
+            })
+        })
+        .log();
+}
+
+/// Choose how synthetic keyboard events, sent for keys held while a window gains focus,
+/// are delivered to the host.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_synthetic_key_policy(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    policy: WinitSyntheticKeyPolicy,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| settings.synthetic_keys = policy)
+        })
+        .log();
+}
+
+/// Choose whether `RedrawRequested` is also delivered through the event queue
+/// for hosts that drive rendering by polling events instead of redraw listeners.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_enqueue_redraw_requested(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    enqueue_redraw_requested: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| {
+                settings.enqueue_redraw_requested = enqueue_redraw_requested
+            })
+        })
+        .log();
+}
+
+/// Opt in to replacing a trailing cursor moved event of the same window instead of appending
+/// a new one, so that only the latest position is kept between pops.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_coalesce_cursor_moves(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    coalesce_cursor_moves: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.set_coalesce_cursor_moves(coalesce_cursor_moves)
+        })
+        .log();
+}
+
+/// Approximate heap memory held by the pending events and the windows registry, in bytes.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_memory_usage(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> usize {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.memory_usage())
+        .or_log(0)
+}
+
+/// Release the memory the event queue and the windows registry keep after bursts of events
+/// or windows. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_trim(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.trim())
+        .log();
+}
+
+/// Post a message with a tag and a copy of the payload to the window. The message is enqueued
+/// as a [`WinitUserEvent`] and signals the semaphore like the events of the platform, so that
+/// host threads can wake up the UI pump. The payload can be null if its length is 0.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_push_user_event(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_id: usize,
+    tag: u64,
+    payload: *const u8,
+    payload_len: usize,
+) -> WinitErrorCode {
+    let payload = if payload.is_null() || payload_len == 0 {
+        vec![]
+    } else {
+        unsafe { std::slice::from_raw_parts(payload, payload_len) }.to_vec()
+    };
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.push_user_event(
+                WindowId::from_raw(window_id),
+                WinitUserEvent::new(tag, payload),
+            )
+        })
+        .map_err(WinitError::from)
+        .and_then(|result| result)
+        .into_error_code()
+}
+
+/// Choose which event types are enqueued, the bit of an event type is `1 << event_type`.
+/// Pass all bits set to enqueue every event, which is the default.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_event_mask(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    event_mask: u64,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.set_event_mask(event_mask))
+        .log();
+}
+
+/// Let the IME candidate window automatically follow the caret area posted by the host with
+/// [`crate::winit_window_handle_post_ime_caret_area`], applied before the event loop waits.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_follow_ime_caret(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    follow_ime_caret: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .update_settings(|settings| settings.follow_ime_caret = follow_ime_caret)
+        })
+        .log();
+}
+
+/// Choose whether received text is delivered as UTF-8 string boxes
+/// (`Winit30WindowEventReceivedText`) or as arrays of UTF-32 code points
+/// (`Winit30WindowEventReceivedTextUtf32`).
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_text_encoding(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    text_encoding: WinitTextEncoding,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| settings.text_encoding = text_encoding)
+        })
+        .log();
+}
+
+/// Choose whether files dropped together are delivered as one `Winit30WindowEventDroppedFiles`
+/// event with a list of paths, or as a dropped file event for each file (default).
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_batch_dropped_files(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    batch_dropped_files: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .update_settings(|settings| settings.batch_dropped_files = batch_dropped_files)
+        })
+        .log();
+}
+
+/// Choose whether mouse, touch and pen input is delivered as separate mouse input, cursor and
+/// touch events (default) or as `Winit30WindowEventPointer` events for all kinds of devices.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_pointer_event_mode(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    pointer_event_mode: u8,
+) -> WinitErrorCode {
+    WinitPointerEventMode::try_from(pointer_event_mode)
+        .and_then(|pointer_event_mode| {
+            application_handle
+                .with_ref_ok(|application_handle| {
+                    application_handle
+                        .update_settings(|settings| settings.pointer_events = pointer_event_mode)
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+/// Choose whether received text events carry the text of a key press with all modifiers
+/// applied (default) or the text produced by the keyboard layout. Keyboard input events
+/// always carry both.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_text_source(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    text_source: WinitTextSource,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.update_settings(|settings| settings.text_source = text_source)
+        })
+        .log();
+}
+
+/// Opt in to raw device button and key events, delivered even when no window is focused.
+/// Meant for global input monitoring.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_raw_device_input(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    raw_device_input: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .update_settings(|settings| settings.raw_device_input = raw_device_input);
+            // device event listening is updated from the UI thread
+            application_handle.wake_up();
+        })
+        .log();
+}
+
+/// Opt in to the relative motion events of pointer devices
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_device_pointer_motion(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    device_pointer_motion: bool,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .update_settings(|settings| settings.device_pointer_motion = device_pointer_motion)
+        })
+        .log();
+}
+
+/// Take all pending events as a batch. The batch owns the events and must be released by the host.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_swap_events(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> *mut ValueBox<EventBatch> {
+    application_handle
+        .with_ref_ok(|application_handle| value_box!(application_handle.swap_events()).into_raw())
+        .or_log(std::ptr::null_mut())
+}
+
+/// Start watching the event queue: if it holds at least `threshold` events for longer than
+/// `timeout_millis` the callback is invoked with the queue length and `true`, and once the host
+/// catches up again with `false`. When `drop_input_events` is set, input events are not enqueued
+/// while the queue is stalled, lifecycle events are always kept.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_queue_watchdog(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    threshold: usize,
+    timeout_millis: u64,
+    drop_input_events: bool,
+    callback: Option<unsafe extern "C" fn(*const c_void, usize, bool)>,
+    thunk: *const c_void,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            let mut watchdog = EventQueueWatchdog::new(
+                threshold,
+                Duration::from_millis(timeout_millis),
+                drop_input_events,
+            );
+            if let Some(callback) = callback {
+                watchdog = watchdog.with_callback(EventQueueWatchdogCallback::new(callback, thunk));
+            }
+            application_handle.set_queue_watchdog(Some(watchdog));
+        })
+        .log();
+}
+
+/// Register a clock used to stamp events so that timestamps share the host's time base.
+/// The callback is called from the UI thread. Pass a null callback to go back to the default
+/// monotonic clock measuring nanoseconds since the application start.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_event_clock(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    callback: Option<unsafe extern "C" fn(*const c_void) -> u64>,
+    thunk: *const c_void,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .set_host_clock(callback.map(|callback| HostClock::new(callback, thunk)))
+        })
+        .log();
+}
+
+/// Signal a semaphore whenever events of the types in the mask are enqueued for a given window.
+/// The bit of an event type in the mask is `1 << event_type`. Takes ownership of the semaphore.
+/// Returns the id of the subscription, or 0 on error.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_subscribe(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    window_id: usize,
+    event_mask: u64,
+    semaphore: *mut ValueBox<SemaphoreSignaller>,
+) -> usize {
+    application_handle
+        .with_ref(|application_handle| {
+            semaphore.take_value().map(|semaphore| {
+                application_handle.subscribe(WindowId::from_raw(window_id), event_mask, semaphore)
+            })
+        })
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_application_handle_unsubscribe(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    subscription_id: usize,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.unsubscribe(subscription_id))
+        .log();
+}
+
+/// Register a callback telling the host that the UI thread is alive, called at most once per
+/// `interval_millis` from the UI thread, including while a native modal loop (menu, live resize
+/// or move) is running and events are not being popped. Pass a null callback to remove it.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_set_liveness_callback(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    interval_millis: u64,
+    callback: Option<unsafe extern "C" fn(*const c_void)>,
+    thunk: *const c_void,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.set_liveness_notifier(callback.map(|callback| {
+                LivenessNotifier::new(
+                    Duration::from_millis(interval_millis),
+                    LivenessCallback::new(callback, thunk),
+                )
+            }))
+        })
+        .log();
+}
+
+/// The modifiers currently held down, so that the host does not need to replay the modifiers
+/// changed events to know them. Nothing is held before the first modifiers changed event.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_get_modifiers(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> WinitEventModifiersChanged {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.modifiers())
+        .or_log(WinitEventModifiersChanged::default())
+}
+
+/// Percentiles of the time the latest input events waited in the main queue between being
+/// enqueued and popped, in nanoseconds. Unlike the latency probe it is always measured and
+/// does not include the time it took to convert the events.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_get_queue_latency(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> WinitLatencyReport {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.queue_latency())
+        .or_log(WinitLatencyReport::default())
+}
+
+/// Enable the latency probe diagnostic mode: the time input events spend in the queue is
+/// measured with the event clock and reported as percentiles every `interval_millis`.
+/// The callback is called from the thread that pops events.
+#[no_mangle]
+pub extern "C" fn winit_application_handle_enable_latency_probe(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+    interval_millis: u64,
+    callback: unsafe extern "C" fn(*const c_void, WinitLatencyReport),
+    thunk: *const c_void,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle.set_latency_probe(Some(LatencyProbe::new(
+                Duration::from_millis(interval_millis),
+                LatencyProbeCallback::new(callback, thunk),
+            )))
+        })
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_application_handle_disable_latency_probe(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.set_latency_probe(None))
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_application_handle_remove_queue_watchdog(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.set_queue_watchdog(None))
+        .log();
+}
+
 #[no_mangle]
 pub extern "C" fn winit_application_handle_release_get_type(
     application_handle: *mut ValueBox<ApplicationHandle>,
@@ -449,6 +2415,56 @@ pub extern "C" fn winit_application_handle_release_get_type(
         .or_log(WinitEventLoopType::Unknown)
 }
 
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_raw_display_handle(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> *mut VeryRawDisplayHandle {
+    application_handle
+        .with_ref_ok(|application_handle| {
+            application_handle
+                .raw_display_handle()
+                .map(|handle| VeryRawDisplayHandle::from(handle).into())
+                .unwrap_or(std::ptr::null_mut())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+/// Can be called from any thread
+#[cfg(wayland_platform)]
+#[no_mangle]
+pub extern "C" fn winit_application_handle_get_wayland_display(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> *mut c_void {
+    application_handle
+        .with_ref(
+            |application_handle| match application_handle.raw_display_handle() {
+                Some(RawDisplayHandle::Wayland(handle)) => Ok(handle.display.as_ptr()),
+                handle => Err(anyhow!("Expected a Wayland, got {:?}", handle).into()),
+            },
+        )
+        .or_log(std::ptr::null_mut())
+}
+
+/// Can be called from any thread
+#[cfg(x11_platform)]
+#[no_mangle]
+pub extern "C" fn winit_application_handle_get_xlib_display(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> *mut c_void {
+    application_handle
+        .with_ref(
+            |application_handle| match application_handle.raw_display_handle() {
+                Some(RawDisplayHandle::Xlib(handle)) => Ok(handle
+                    .display
+                    .map(|display| display.as_ptr())
+                    .unwrap_or(std::ptr::null_mut())),
+                handle => Err(anyhow!("Expected an Xlib, got {:?}", handle).into()),
+            },
+        )
+        .or_log(std::ptr::null_mut())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_application_handle_release(
     application_handle: *mut ValueBox<ApplicationHandle>,
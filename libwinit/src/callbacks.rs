@@ -0,0 +1,120 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::os::raw::c_void;
+
+static CALLBACKS: Mutex<CallbackRegistry> = Mutex::new(CallbackRegistry::new());
+
+/// Keeps track of host thunks that must no longer be passed to their callbacks, for example
+/// because the host garbage collector moved or freed them. The host registers a thunk to get
+/// a token and invalidates the token once the thunk is gone. Callbacks remember the token of
+/// their thunk when they are created and become no-ops once that token is invalidated, so a
+/// new thunk at a reused address is not affected. Thunks that were never registered are
+/// always called.
+#[derive(Debug)]
+pub struct CallbackRegistry {
+    next_token: usize,
+    /// The thunks of the tokens that were not invalidated yet
+    thunks: Option<HashMap<usize, usize>>,
+}
+
+impl CallbackRegistry {
+    const fn new() -> Self {
+        Self {
+            next_token: 0,
+            thunks: None,
+        }
+    }
+
+    fn register(&mut self, thunk: *const c_void) -> usize {
+        self.next_token += 1;
+        self.thunks
+            .get_or_insert_with(Default::default)
+            .insert(self.next_token, thunk as usize);
+        self.next_token
+    }
+
+    fn invalidate(&mut self, token: usize) {
+        if let Some(thunks) = self.thunks.as_mut() {
+            thunks.remove(&token);
+        }
+    }
+
+    fn is_valid_token(&self, token: usize) -> bool {
+        token == 0
+            || self
+                .thunks
+                .as_ref()
+                .map(|thunks| thunks.contains_key(&token))
+                .unwrap_or(false)
+    }
+
+    /// The latest token of the thunk that is still valid
+    fn token_of_thunk(&self, thunk: *const c_void) -> Option<usize> {
+        self.thunks.as_ref().and_then(|thunks| {
+            thunks
+                .iter()
+                .filter(|(_, registered_thunk)| **registered_thunk == thunk as usize)
+                .map(|(token, _)| *token)
+                .max()
+        })
+    }
+
+    /// The token the thunk was registered with, 0 if it was never registered.
+    /// Callbacks look it up once when they are created and keep it for [`Self::is_valid`]
+    pub fn token_of(thunk: *const c_void) -> usize {
+        CALLBACKS.lock().token_of_thunk(thunk).unwrap_or(0)
+    }
+
+    /// Check if the callback created with the given token may still be called
+    pub fn is_valid(token: usize) -> bool {
+        let is_valid = CALLBACKS.lock().is_valid_token(token);
+        if !is_valid {
+            trace!("Skipping a callback with an invalidated token {}", token);
+        }
+        is_valid
+    }
+}
+
+/// Register a thunk passed to callbacks and return a token to invalidate it later.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_callback_register(thunk: *const c_void) -> usize {
+    CALLBACKS.lock().register(thunk)
+}
+
+/// Turn all callbacks created with the token into no-ops.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_callback_invalidate(token: usize) {
+    CALLBACKS.lock().invalidate(token);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidates_registered_tokens() {
+        let mut registry = CallbackRegistry::new();
+        let thunk = 42 as *const c_void;
+        let other_thunk = 43 as *const c_void;
+
+        let token = registry.register(thunk);
+        let other_token = registry.register(thunk);
+        assert_eq!(registry.token_of_thunk(thunk), Some(other_token));
+        assert_eq!(registry.token_of_thunk(other_thunk), None);
+
+        registry.invalidate(token);
+        assert!(!registry.is_valid_token(token));
+        assert!(registry.is_valid_token(other_token));
+        assert!(registry.is_valid_token(0));
+
+        registry.invalidate(other_token);
+        assert_eq!(registry.token_of_thunk(thunk), None);
+
+        // a new thunk at the same address gets its own token
+        let token = registry.register(thunk);
+        assert!(registry.is_valid_token(token));
+        assert_eq!(registry.token_of_thunk(thunk), Some(token));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{ApplicationHandle, WinitEventLoopType};
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::raw_window_handle::RawDisplayHandle;
+
+/// What the windowing backend the event loop runs on is able to do,
+/// so that the host can pick suitable defaults for its UI.
+/// Probed from the windowing system when the event loop is built where it can tell,
+/// for example whether an X11 compositor is running or which protocols a Wayland compositor
+/// offers, otherwise the defaults of the backend. The host should still handle a failing request
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct WinitCapabilities {
+    /// Windows can have a transparent background. X11 also needs a running compositor
+    pub transparency: bool,
+    /// The scale factor can be a non-integer value, such as 1.25
+    pub fractional_scaling: bool,
+    /// Windows can switch the video mode of a monitor when going fullscreen
+    pub exclusive_fullscreen: bool,
+    /// The cursor can be locked in place, rather than just confined to the window
+    pub cursor_lock: bool,
+    /// Window decorations are drawn by the system rather than by winit.
+    /// Wayland compositors do not have to provide them
+    pub server_side_decorations: bool,
+}
+
+impl WinitCapabilities {
+    /// Refine the defaults of the backend with what the windowing system reports.
+    /// Must be called from the UI thread while the event loop is alive
+    pub fn probe(event_loop_type: WinitEventLoopType, display_handle: RawDisplayHandle) -> Self {
+        #[allow(unused_mut)]
+        let mut capabilities = Self::from(event_loop_type);
+        match display_handle {
+            #[cfg(x11_platform)]
+            RawDisplayHandle::Xlib(handle) => {
+                if let Some(display) = handle.display {
+                    if let Some(has_compositor) =
+                        x11::has_compositor(display.as_ptr(), handle.screen)
+                    {
+                        capabilities.transparency = has_compositor;
+                    }
+                }
+            }
+            #[cfg(wayland_platform)]
+            RawDisplayHandle::Wayland(handle) => {
+                if let Some(globals) = wayland::globals(handle.display.as_ptr()) {
+                    let has_global = |name: &str| globals.iter().any(|global| global == name);
+                    capabilities.fractional_scaling = has_global("wp_fractional_scale_manager_v1");
+                    capabilities.cursor_lock = has_global("zwp_pointer_constraints_v1");
+                    capabilities.server_side_decorations = has_global("zxdg_decoration_manager_v1");
+                }
+            }
+            _ => {}
+        }
+        capabilities
+    }
+}
+
+/// The defaults of each backend, used where the windowing system can not be asked
+impl From<WinitEventLoopType> for WinitCapabilities {
+    fn from(event_loop_type: WinitEventLoopType) -> Self {
+        match event_loop_type {
+            WinitEventLoopType::Windows => Self {
+                transparency: true,
+                fractional_scaling: true,
+                exclusive_fullscreen: true,
+                cursor_lock: false,
+                server_side_decorations: true,
+            },
+            WinitEventLoopType::MacOS => Self {
+                transparency: true,
+                fractional_scaling: false,
+                exclusive_fullscreen: true,
+                cursor_lock: true,
+                server_side_decorations: true,
+            },
+            WinitEventLoopType::X11 => Self {
+                transparency: true,
+                fractional_scaling: true,
+                exclusive_fullscreen: true,
+                cursor_lock: false,
+                server_side_decorations: true,
+            },
+            WinitEventLoopType::Wayland => Self {
+                transparency: true,
+                fractional_scaling: true,
+                exclusive_fullscreen: false,
+                cursor_lock: true,
+                server_side_decorations: false,
+            },
+            // the density of Android screens is rarely a whole number
+            WinitEventLoopType::Android => Self {
+                fractional_scaling: true,
+                ..Self::default()
+            },
+            WinitEventLoopType::IOS => Self {
+                transparency: true,
+                ..Self::default()
+            },
+            // the canvas can be transparent, the browser zoom scales by any factor
+            // and the pointer lock API locks the cursor
+            WinitEventLoopType::Web => Self {
+                transparency: true,
+                fractional_scaling: true,
+                cursor_lock: true,
+                ..Self::default()
+            },
+            WinitEventLoopType::Unknown => Self::default(),
+        }
+    }
+}
+
+/// The system libraries are loaded at runtime, as winit does, the event loop already has
+/// them loaded so opening them again only takes a reference
+#[cfg(free_unix)]
+struct Library(*mut std::ffi::c_void);
+
+#[cfg(free_unix)]
+impl Library {
+    /// The name must be nul terminated
+    fn open(name: &[u8]) -> Option<Self> {
+        let library =
+            unsafe { libc::dlopen(name.as_ptr() as *const _, libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        (!library.is_null()).then_some(Self(library))
+    }
+
+    /// The name must be nul terminated and `T` a pointer type matching the symbol
+    unsafe fn symbol<T: Copy>(&self, name: &[u8]) -> Option<T> {
+        let symbol = libc::dlsym(self.0, name.as_ptr() as *const _);
+        (!symbol.is_null()).then(|| std::mem::transmute_copy(&symbol))
+    }
+}
+
+#[cfg(free_unix)]
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { libc::dlclose(self.0) };
+    }
+}
+
+#[cfg(x11_platform)]
+mod x11 {
+    use super::Library;
+    use std::ffi::{c_char, c_int, c_ulong, c_void, CString};
+
+    type XInternAtom = unsafe extern "C" fn(*mut c_void, *const c_char, c_int) -> c_ulong;
+    type XGetSelectionOwner = unsafe extern "C" fn(*mut c_void, c_ulong) -> c_ulong;
+
+    /// A running compositor owns the `_NET_WM_CM_S<screen>` selection, windows can only be
+    /// transparent with one. None if the server could not be asked
+    pub fn has_compositor(display: *mut c_void, screen: c_int) -> Option<bool> {
+        let library = Library::open(b"libX11.so.6\0")?;
+        let selection = CString::new(format!("_NET_WM_CM_S{}", screen)).ok()?;
+        unsafe {
+            let intern_atom: XInternAtom = library.symbol(b"XInternAtom\0")?;
+            let get_selection_owner: XGetSelectionOwner =
+                library.symbol(b"XGetSelectionOwner\0")?;
+
+            let atom = intern_atom(display, selection.as_ptr(), 0);
+            if atom == 0 {
+                return None;
+            }
+            Some(get_selection_owner(display, atom) != 0)
+        }
+    }
+}
+
+#[cfg(wayland_platform)]
+mod wayland {
+    use super::Library;
+    use std::ffi::{c_char, c_int, c_void, CStr};
+
+    /// The opcode of `wl_display.get_registry`
+    const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+
+    type WlDisplayCreateQueue = unsafe extern "C" fn(*mut c_void) -> *mut c_void;
+    type WlDisplayRoundtripQueue = unsafe extern "C" fn(*mut c_void, *mut c_void) -> c_int;
+    type WlEventQueueDestroy = unsafe extern "C" fn(*mut c_void);
+    type WlProxyCreateWrapper = unsafe extern "C" fn(*mut c_void) -> *mut c_void;
+    type WlProxyWrapperDestroy = unsafe extern "C" fn(*mut c_void);
+    type WlProxySetQueue = unsafe extern "C" fn(*mut c_void, *mut c_void);
+    type WlProxyMarshalConstructor =
+        unsafe extern "C" fn(*mut c_void, u32, *const c_void, ...) -> *mut c_void;
+    type WlProxyAddListener =
+        unsafe extern "C" fn(*mut c_void, *const RegistryListener, *mut c_void) -> c_int;
+    type WlProxyDestroy = unsafe extern "C" fn(*mut c_void);
+
+    #[repr(C)]
+    struct RegistryListener {
+        global: unsafe extern "C" fn(*mut c_void, *mut c_void, u32, *const c_char, u32),
+        global_remove: unsafe extern "C" fn(*mut c_void, *mut c_void, u32),
+    }
+
+    static REGISTRY_LISTENER: RegistryListener = RegistryListener {
+        global: on_global,
+        global_remove: on_global_remove,
+    };
+
+    unsafe extern "C" fn on_global(
+        globals: *mut c_void,
+        _registry: *mut c_void,
+        _name: u32,
+        interface: *const c_char,
+        _version: u32,
+    ) {
+        let globals = &mut *(globals as *mut Vec<String>);
+        if !interface.is_null() {
+            globals.push(CStr::from_ptr(interface).to_string_lossy().into_owned());
+        }
+    }
+
+    unsafe extern "C" fn on_global_remove(_globals: *mut c_void, _registry: *mut c_void, _: u32) {}
+
+    /// The interface names of the globals the compositor advertises, listed on a queue of
+    /// our own so that the events of winit are not dispatched. None if they could not be listed
+    pub fn globals(display: *mut c_void) -> Option<Vec<String>> {
+        let library = Library::open(b"libwayland-client.so.0\0")?;
+        unsafe {
+            let create_queue: WlDisplayCreateQueue =
+                library.symbol(b"wl_display_create_queue\0")?;
+            let roundtrip_queue: WlDisplayRoundtripQueue =
+                library.symbol(b"wl_display_roundtrip_queue\0")?;
+            let destroy_queue: WlEventQueueDestroy = library.symbol(b"wl_event_queue_destroy\0")?;
+            let create_wrapper: WlProxyCreateWrapper =
+                library.symbol(b"wl_proxy_create_wrapper\0")?;
+            let destroy_wrapper: WlProxyWrapperDestroy =
+                library.symbol(b"wl_proxy_wrapper_destroy\0")?;
+            let set_queue: WlProxySetQueue = library.symbol(b"wl_proxy_set_queue\0")?;
+            let marshal_constructor: WlProxyMarshalConstructor =
+                library.symbol(b"wl_proxy_marshal_constructor\0")?;
+            let add_listener: WlProxyAddListener = library.symbol(b"wl_proxy_add_listener\0")?;
+            let destroy_proxy: WlProxyDestroy = library.symbol(b"wl_proxy_destroy\0")?;
+            let registry_interface: *const c_void = library.symbol(b"wl_registry_interface\0")?;
+
+            let queue = create_queue(display);
+            if queue.is_null() {
+                return None;
+            }
+            let wrapper = create_wrapper(display);
+            if wrapper.is_null() {
+                destroy_queue(queue);
+                return None;
+            }
+            set_queue(wrapper, queue);
+            let registry = marshal_constructor(
+                wrapper,
+                WL_DISPLAY_GET_REGISTRY,
+                registry_interface,
+                std::ptr::null_mut::<c_void>(),
+            );
+            destroy_wrapper(wrapper);
+            if registry.is_null() {
+                destroy_queue(queue);
+                return None;
+            }
+
+            let mut globals: Vec<String> = vec![];
+            let is_listed = add_listener(
+                registry,
+                &REGISTRY_LISTENER,
+                &mut globals as *mut Vec<String> as *mut c_void,
+            ) == 0
+                && roundtrip_queue(display, queue) >= 0;
+            destroy_proxy(registry);
+            destroy_queue(queue);
+            is_listed.then_some(globals)
+        }
+    }
+}
+
+/// All capabilities are reported as missing until the event loop is initialized.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_application_handle_get_capabilities(
+    application_handle: *mut ValueBox<ApplicationHandle>,
+) -> WinitCapabilities {
+    application_handle
+        .with_ref_ok(|application_handle| application_handle.capabilities())
+        .or_log(WinitCapabilities::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_nothing_for_unknown_backends() {
+        assert_eq!(
+            WinitCapabilities::from(WinitEventLoopType::Unknown),
+            WinitCapabilities::default()
+        );
+        assert!(!WinitCapabilities::from(WinitEventLoopType::Wayland).exclusive_fullscreen);
+    }
+
+    #[test]
+    fn falls_back_to_the_defaults_of_the_backend() {
+        let display_handle =
+            RawDisplayHandle::Web(winit::raw_window_handle::WebDisplayHandle::new());
+        let capabilities = WinitCapabilities::probe(WinitEventLoopType::Web, display_handle);
+
+        assert_eq!(
+            capabilities,
+            WinitCapabilities::from(WinitEventLoopType::Web)
+        );
+        assert!(capabilities.cursor_lock);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{ClickSettings, WinitEventMouseButton};
+use std::time::Instant;
+use winit::dpi::PhysicalPosition;
+
+/// Counts the consecutive presses of a mouse button, so that double and triple clicks
+/// are recognised the same way for every host
+#[derive(Debug, Default)]
+pub struct ClickCounter {
+    last_press: Option<ButtonPress>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ButtonPress {
+    button: WinitEventMouseButton,
+    position: PhysicalPosition<f64>,
+    time: Instant,
+    count: u32,
+}
+
+impl ClickCounter {
+    /// The number of the press in the series of clicks, 1 for a single click
+    pub fn press(
+        &mut self,
+        button: WinitEventMouseButton,
+        position: PhysicalPosition<f64>,
+        time: Instant,
+        settings: &ClickSettings,
+    ) -> u32 {
+        let count = match self.last_press {
+            Some(last_press)
+                if last_press.button == button
+                    && time.saturating_duration_since(last_press.time) <= settings.interval
+                    && distance(last_press.position, position) <= settings.distance =>
+            {
+                last_press.count + 1
+            }
+            _ => 1,
+        };
+        self.last_press = Some(ButtonPress {
+            button,
+            position,
+            time,
+            count,
+        });
+        count
+    }
+
+    /// A release has the count of the press it ends
+    pub fn release(&self, button: WinitEventMouseButton) -> u32 {
+        self.last_press
+            .filter(|last_press| last_press.button == button)
+            .map(|last_press| last_press.count)
+            .unwrap_or(1)
+    }
+}
+
+fn distance(from: PhysicalPosition<f64>, to: PhysicalPosition<f64>) -> f64 {
+    (to.x - from.x).hypot(to.y - from.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn counts_nearby_presses_within_the_interval() {
+        let settings = ClickSettings::default();
+        let button = WinitEventMouseButton::default();
+        let start = Instant::now();
+        let mut counter = ClickCounter::default();
+
+        assert_eq!(
+            counter.press(button, PhysicalPosition::new(10.0, 10.0), start, &settings),
+            1
+        );
+        assert_eq!(
+            counter.press(
+                button,
+                PhysicalPosition::new(11.0, 12.0),
+                start + Duration::from_millis(200),
+                &settings
+            ),
+            2
+        );
+        assert_eq!(counter.release(button), 2);
+        assert_eq!(
+            counter.press(
+                button,
+                PhysicalPosition::new(11.0, 12.0),
+                start + Duration::from_millis(1000),
+                &settings
+            ),
+            1
+        );
+        assert_eq!(
+            counter.press(
+                button,
+                PhysicalPosition::new(40.0, 12.0),
+                start + Duration::from_millis(1100),
+                &settings
+            ),
+            1
+        );
+    }
+}
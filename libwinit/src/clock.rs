@@ -0,0 +1,64 @@
+use crate::CallbackRegistry;
+use std::os::raw::c_void;
+use std::time::Instant;
+
+/// Stamps events at the moment they are received from winit.
+/// By default timestamps are nanoseconds of a monotonic clock started together with the
+/// application. The host may register its own clock so that event timestamps are expressed
+/// in the host's time base.
+#[derive(Debug, Clone)]
+pub struct EventClock {
+    start: Instant,
+    host_clock: Option<HostClock>,
+}
+
+impl EventClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            host_clock: None,
+        }
+    }
+
+    pub fn set_host_clock(&mut self, host_clock: Option<HostClock>) {
+        self.host_clock = host_clock;
+    }
+
+    pub fn now(&self) -> u64 {
+        match &self.host_clock {
+            Some(host_clock) if CallbackRegistry::is_valid(host_clock.token) => host_clock.now(),
+            _ => self.start.elapsed().as_nanos() as u64,
+        }
+    }
+}
+
+impl Default for EventClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A clock provided by the host, called from the UI thread for every received event.
+#[derive(Debug, Clone)]
+pub struct HostClock {
+    callback: unsafe extern "C" fn(*const c_void) -> u64,
+    thunk: *const c_void,
+    token: usize,
+}
+
+unsafe impl Send for HostClock {}
+unsafe impl Sync for HostClock {}
+
+impl HostClock {
+    pub fn new(callback: unsafe extern "C" fn(*const c_void) -> u64, thunk: *const c_void) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        unsafe { (self.callback)(self.thunk) }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{ReturnErrorCode, WindowHandle, WinitError, WinitErrorCode};
+use geometry_box::{PointBox, SizeBox};
+use value_box::{BoxerError, ValueBox, ValueBoxPointer};
+use winit::monitor::MonitorHandle;
+
+/// Passed to the conversion functions as its `u8` value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitCoordinateConversion {
+    PhysicalToLogical,
+    LogicalToPhysical,
+}
+
+/// A value out of the range of the enum would be undefined behaviour, it is rejected instead
+impl TryFrom<u8> for WinitCoordinateConversion {
+    type Error = WinitError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::PhysicalToLogical),
+            1 => Ok(Self::LogicalToPhysical),
+            _ => Err(WinitError::new(
+                WinitErrorCode::InvalidArgument,
+                format!("Unknown coordinate conversion {}", value),
+            )),
+        }
+    }
+}
+
+/// Physical coordinates are rounded to whole pixels, logical ones are left as they are
+fn convert(value: f64, scale_factor: f64, conversion: WinitCoordinateConversion) -> f64 {
+    match conversion {
+        WinitCoordinateConversion::PhysicalToLogical => value / scale_factor,
+        WinitCoordinateConversion::LogicalToPhysical => (value * scale_factor).round(),
+    }
+}
+
+pub fn convert_point(
+    x: f64,
+    y: f64,
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+) -> (f64, f64) {
+    (
+        convert(x, scale_factor, conversion),
+        convert(y, scale_factor, conversion),
+    )
+}
+
+pub fn convert_size(
+    width: f64,
+    height: f64,
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+) -> (f64, f64) {
+    (
+        convert(width, scale_factor, conversion),
+        convert(height, scale_factor, conversion),
+    )
+}
+
+/// The edges of the rectangle are converted rather than its size,
+/// so that adjacent rectangles stay adjacent after rounding
+pub fn convert_rect(
+    (x, y): (f64, f64),
+    (width, height): (f64, f64),
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+) -> ((f64, f64), (f64, f64)) {
+    let (left, top) = convert_point(x, y, scale_factor, conversion);
+    let (right, bottom) = convert_point(x + width, y + height, scale_factor, conversion);
+    ((left, top), (right - left, bottom - top))
+}
+
+fn convert_point_box(
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+    point: *mut ValueBox<PointBox<f64>>,
+) -> Result<(), BoxerError> {
+    point.with_mut_ok(|point| {
+        (point.x, point.y) = convert_point(point.x, point.y, scale_factor, conversion);
+    })
+}
+
+fn convert_size_box(
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> Result<(), BoxerError> {
+    size.with_mut_ok(|size| {
+        (size.width, size.height) = convert_size(size.width, size.height, scale_factor, conversion);
+    })
+}
+
+fn convert_rect_boxes(
+    scale_factor: f64,
+    conversion: WinitCoordinateConversion,
+    origin: *mut ValueBox<PointBox<f64>>,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> Result<(), BoxerError> {
+    origin.with_mut(|origin| {
+        size.with_mut_ok(|size| {
+            ((origin.x, origin.y), (size.width, size.height)) = convert_rect(
+                (origin.x, origin.y),
+                (size.width, size.height),
+                scale_factor,
+                conversion,
+            );
+        })
+    })
+}
+
+/// Convert a point in place with the scale factor of the window.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_convert_point(
+    window: *mut ValueBox<WindowHandle>,
+    conversion: u8,
+    point: *mut ValueBox<PointBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            window
+                .with_ref(|window| convert_point_box(window.scale_factor(), conversion, point))
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+/// Convert a size in place with the scale factor of the window.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_convert_size(
+    window: *mut ValueBox<WindowHandle>,
+    conversion: u8,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            window
+                .with_ref(|window| convert_size_box(window.scale_factor(), conversion, size))
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+/// Convert a rectangle given by its origin and size in place with the scale factor of the
+/// window. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_convert_rect(
+    window: *mut ValueBox<WindowHandle>,
+    conversion: u8,
+    origin: *mut ValueBox<PointBox<f64>>,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            window
+                .with_ref(|window| {
+                    convert_rect_boxes(window.scale_factor(), conversion, origin, size)
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+#[no_mangle]
+pub extern "C" fn winit_monitor_convert_point(
+    monitor_handle: *mut ValueBox<MonitorHandle>,
+    conversion: u8,
+    point: *mut ValueBox<PointBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            monitor_handle
+                .with_ref(|monitor_handle| {
+                    convert_point_box(monitor_handle.scale_factor(), conversion, point)
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+#[no_mangle]
+pub extern "C" fn winit_monitor_convert_size(
+    monitor_handle: *mut ValueBox<MonitorHandle>,
+    conversion: u8,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            monitor_handle
+                .with_ref(|monitor_handle| {
+                    convert_size_box(monitor_handle.scale_factor(), conversion, size)
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+#[no_mangle]
+pub extern "C" fn winit_monitor_convert_rect(
+    monitor_handle: *mut ValueBox<MonitorHandle>,
+    conversion: u8,
+    origin: *mut ValueBox<PointBox<f64>>,
+    size: *mut ValueBox<SizeBox<f64>>,
+) -> WinitErrorCode {
+    WinitCoordinateConversion::try_from(conversion)
+        .and_then(|conversion| {
+            monitor_handle
+                .with_ref(|monitor_handle| {
+                    convert_rect_boxes(monitor_handle.scale_factor(), conversion, origin, size)
+                })
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_adjacent_rects_adjacent() {
+        let conversion = WinitCoordinateConversion::LogicalToPhysical;
+        let ((left, _), (width, _)) = convert_rect((0.0, 0.0), (11.0, 10.0), 1.5, conversion);
+        let ((next_left, _), _) = convert_rect((11.0, 0.0), (11.0, 10.0), 1.5, conversion);
+
+        assert_eq!(left + width, next_left);
+        assert_eq!(convert_point(11.0, 0.0, 1.5, conversion), (17.0, 0.0));
+    }
+
+    #[test]
+    fn rejects_unknown_conversions() {
+        assert_eq!(
+            WinitCoordinateConversion::try_from(1).ok(),
+            Some(WinitCoordinateConversion::LogicalToPhysical)
+        );
+        assert_eq!(
+            WinitCoordinateConversion::try_from(2)
+                .map_err(|error| error.code())
+                .err(),
+            Some(WinitErrorCode::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+use winit::event::DeviceId;
+
+/// Assigns stable integers to winit devices in the order they are first seen,
+/// so that the host can tell apart events coming from different devices.
+/// Events without a known device get 0.
+#[derive(Debug, Default)]
+pub struct DeviceIds {
+    ids: HashMap<DeviceId, i64>,
+}
+
+impl DeviceIds {
+    pub fn get(&mut self, device_id: Option<DeviceId>) -> i64 {
+        match device_id {
+            None => 0,
+            Some(device_id) => {
+                let next_id = self.ids.len() as i64 + 1;
+                *self.ids.entry(device_id).or_insert(next_id)
+            }
+        }
+    }
+}
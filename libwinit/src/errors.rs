@@ -0,0 +1,133 @@
+use parking_lot::Mutex;
+use std::fmt::{Display, Formatter};
+use string_box::StringBox;
+use value_box::{BoxerError, ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::error::RequestError;
+use winit::icon::BadIcon;
+
+/// The last error reported by a fallible function, shared by all threads since failures of
+/// asynchronous requests are detected on the UI thread.
+static LAST_ERROR: Mutex<Option<WinitError>> = Mutex::new(None);
+
+/// Returned by fallible FFI functions instead of silently logging the failure.
+/// Details of the failure are available through [`winit_last_error_get_message`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitErrorCode {
+    Ok,
+    /// One of the passed value boxes is null or its value was already taken
+    InvalidHandle,
+    /// The event loop is no longer running and can not handle requests
+    EventLoopClosed,
+    /// The request is not supported by the platform
+    NotSupported,
+    /// The platform failed to fulfill the request
+    OsError,
+    /// One of the passed values is malformed, for example pixels that do not match the size
+    InvalidArgument,
+}
+
+impl Default for WinitErrorCode {
+    fn default() -> Self {
+        Self::Ok
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WinitError {
+    code: WinitErrorCode,
+    message: String,
+}
+
+impl WinitError {
+    pub fn new(code: WinitErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn code(&self) -> WinitErrorCode {
+        self.code
+    }
+
+    /// Log the error and remember it as the last error.
+    /// Returns the code to pass back to the host.
+    pub fn report(self) -> WinitErrorCode {
+        error!("{}", &self);
+        let code = self.code;
+        LAST_ERROR.lock().replace(self);
+        code
+    }
+}
+
+impl Display for WinitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl From<BoxerError> for WinitError {
+    fn from(error: BoxerError) -> Self {
+        Self::new(WinitErrorCode::InvalidHandle, error.to_string())
+    }
+}
+
+impl From<RequestError> for WinitError {
+    fn from(error: RequestError) -> Self {
+        let code = match &error {
+            RequestError::NotSupported(_) => WinitErrorCode::NotSupported,
+            _ => WinitErrorCode::OsError,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<BadIcon> for WinitError {
+    fn from(error: BadIcon) -> Self {
+        Self::new(WinitErrorCode::InvalidArgument, error.to_string())
+    }
+}
+
+pub trait ReturnErrorCode {
+    /// Report a failure through the last-error API and convert the result to its code
+    fn into_error_code(self) -> WinitErrorCode;
+}
+
+impl<E: Into<WinitError>> ReturnErrorCode for Result<(), E> {
+    fn into_error_code(self) -> WinitErrorCode {
+        match self {
+            Ok(()) => WinitErrorCode::Ok,
+            Err(error) => error.into().report(),
+        }
+    }
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_last_error_get_code() -> WinitErrorCode {
+    LAST_ERROR
+        .lock()
+        .as_ref()
+        .map(|error| error.code())
+        .unwrap_or_default()
+}
+
+/// Write the description of the last error into the string box, leaving it untouched
+/// if there was no error. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_last_error_get_message(message: *mut ValueBox<StringBox>) {
+    message
+        .with_mut_ok(|message| {
+            if let Some(error) = LAST_ERROR.lock().as_ref() {
+                message.set_string(error.message.clone())
+            }
+        })
+        .log();
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_last_error_clear() {
+    LAST_ERROR.lock().take();
+}
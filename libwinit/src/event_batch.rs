@@ -0,0 +1,110 @@
+use crate::{queue_latency_nanos, WinitEventType, WinitWindowEvent};
+use std::collections::VecDeque;
+use std::os::raw::c_void;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+
+/// All events that were pending in the queue at the moment of the swap,
+/// accessible by index.
+#[derive(Debug, Default)]
+pub struct EventBatch {
+    events: Vec<WinitWindowEvent>,
+}
+
+impl EventBatch {
+    pub fn new(events: VecDeque<WinitWindowEvent>) -> Self {
+        Self {
+            events: events.into(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&WinitWindowEvent> {
+        self.events.get(index)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_len(batch: *mut ValueBox<EventBatch>) -> usize {
+    batch.with_ref_ok(|batch| batch.len()).or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_window_id(
+    batch: *mut ValueBox<EventBatch>,
+    index: usize,
+) -> usize {
+    batch
+        .with_ref_ok(|batch| {
+            batch
+                .get(index)
+                .and_then(|event| event.window_id())
+                .map(|id| id.into_raw())
+                .unwrap_or(0)
+        })
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_timestamp(
+    batch: *mut ValueBox<EventBatch>,
+    index: usize,
+) -> u64 {
+    batch
+        .with_ref_ok(|batch| batch.get(index).map(|event| event.timestamp()).unwrap_or(0))
+        .or_log(0)
+}
+
+/// How long the event waited in the queue before the batch was taken, in nanoseconds
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_queue_latency(
+    batch: *mut ValueBox<EventBatch>,
+    index: usize,
+) -> u64 {
+    batch
+        .with_ref_ok(|batch| batch.get(index).map(queue_latency_nanos).unwrap_or(0))
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_event_type(
+    batch: *mut ValueBox<EventBatch>,
+    index: usize,
+) -> WinitEventType {
+    batch
+        .with_ref_ok(|batch| {
+            batch
+                .get(index)
+                .map(|event| event.event_type())
+                .unwrap_or_default()
+        })
+        .or_log(WinitEventType::Unknown)
+}
+
+/// Return a pointer to the event struct at a given index.
+/// The pointer is valid as long as the batch is not released.
+#[no_mangle]
+pub extern "C" fn winit_event_batch_get_event(
+    batch: *mut ValueBox<EventBatch>,
+    index: usize,
+) -> *mut c_void {
+    batch
+        .with_ref_ok(|batch| {
+            batch
+                .get(index)
+                .map(|event| event.as_ptr())
+                .unwrap_or(std::ptr::null_mut())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_batch_release(batch: *mut ValueBox<EventBatch>) {
+    batch.release();
+}
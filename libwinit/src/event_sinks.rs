@@ -0,0 +1,225 @@
+use crate::{
+    ApplicationEvents, CallbackRegistry, SemaphoreSignaller, WinitError, WinitErrorCode,
+    WinitEventType, WinitWindowEvent,
+};
+use std::os::raw::c_void;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+
+/// How events matching the mask of a sink are delivered, passed from the host as its `u8` value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitEventSinkMode {
+    /// The sink receives a copy, the event is still delivered to the main queue
+    Observe,
+    /// The event is delivered to the sink instead of the main queue
+    Route,
+}
+
+impl Default for WinitEventSinkMode {
+    fn default() -> Self {
+        Self::Observe
+    }
+}
+
+/// A value out of the range of the enum would be undefined behaviour, it is rejected instead
+impl TryFrom<u8> for WinitEventSinkMode {
+    type Error = WinitError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Observe),
+            1 => Ok(Self::Route),
+            _ => Err(WinitError::new(
+                WinitErrorCode::InvalidArgument,
+                format!("Unknown event sink mode {}", value),
+            )),
+        }
+    }
+}
+
+/// An independent consumer of events with its own queue, for example an input recorder.
+#[derive(Debug, Clone)]
+pub struct EventSinkHandle {
+    id: usize,
+    events: ApplicationEvents,
+}
+
+impl EventSinkHandle {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn pop_event(&self) -> Option<WinitWindowEvent> {
+        self.events.pop_event()
+    }
+}
+
+#[derive(Debug)]
+struct EventSink {
+    handle: EventSinkHandle,
+    event_mask: u64,
+    mode: WinitEventSinkMode,
+    semaphore: Option<SemaphoreSignaller>,
+}
+
+#[derive(Debug, Default)]
+pub struct EventSinks {
+    next_id: usize,
+    sinks: Vec<EventSink>,
+}
+
+impl EventSinks {
+    /// Register a sink receiving events which type's bit is set in the mask,
+    /// see [`crate::WinitEventType::mask`].
+    pub fn add_sink(
+        &mut self,
+        event_mask: u64,
+        mode: WinitEventSinkMode,
+        semaphore: Option<SemaphoreSignaller>,
+    ) -> EventSinkHandle {
+        self.next_id += 1;
+        let handle = EventSinkHandle {
+            id: self.next_id,
+            events: ApplicationEvents::new(),
+        };
+        self.sinks.push(EventSink {
+            handle: handle.clone(),
+            event_mask,
+            mode,
+            semaphore,
+        });
+        handle
+    }
+
+    pub fn remove_sink(&mut self, id: usize) {
+        self.sinks.retain(|sink| sink.handle.id != id);
+    }
+
+    /// Deliver the event to the matching sinks.
+    /// Returns the event back unless it was routed to one of the sinks.
+    pub fn dispatch(&self, event: WinitWindowEvent) -> Option<WinitWindowEvent> {
+        if self.sinks.is_empty() {
+            return Some(event);
+        }
+
+        let event_mask = event.event_type().mask();
+        let mut route_to = None;
+
+        for sink in self
+            .sinks
+            .iter()
+            .filter(|sink| sink.event_mask & event_mask != 0)
+        {
+            match sink.mode {
+                WinitEventSinkMode::Observe => {
+                    sink.handle.events.push_event(event.clone_event());
+                    sink.signal();
+                }
+                WinitEventSinkMode::Route => {
+                    if route_to.is_none() {
+                        route_to = Some(sink);
+                    }
+                }
+            }
+        }
+
+        match route_to {
+            None => Some(event),
+            Some(sink) => {
+                sink.handle.events.push_event(event);
+                sink.signal();
+                None
+            }
+        }
+    }
+}
+
+impl EventSink {
+    fn signal(&self) {
+        if let Some(semaphore) = &self.semaphore {
+            semaphore.signal();
+        }
+    }
+}
+
+/// Called synchronously from the UI thread with every event as soon as it is received,
+/// before the event reaches the sinks and the main queue. Lets embedders dispatch events
+/// immediately instead of waiting for a semaphore and popping the queue.
+/// The event pointer is only valid during the call.
+#[derive(Debug, Clone)]
+pub struct EventCallback {
+    callback: unsafe extern "C" fn(usize, WinitEventType, *mut c_void, *const c_void),
+    thunk: *const c_void,
+    token: usize,
+    mode: WinitEventSinkMode,
+}
+
+unsafe impl Send for EventCallback {}
+unsafe impl Sync for EventCallback {}
+
+impl EventCallback {
+    pub fn new(
+        callback: unsafe extern "C" fn(usize, WinitEventType, *mut c_void, *const c_void),
+        thunk: *const c_void,
+        mode: WinitEventSinkMode,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+            mode,
+        }
+    }
+
+    /// Returns the event back unless it is routed to the callback
+    pub fn dispatch(&self, event: WinitWindowEvent) -> Option<WinitWindowEvent> {
+        if !CallbackRegistry::is_valid(self.token) {
+            return Some(event);
+        }
+
+        trace_span!("event_callback");
+        let window_id = event.window_id().map(|id| id.into_raw()).unwrap_or(0);
+        unsafe { (self.callback)(window_id, event.event_type(), event.as_ptr(), self.thunk) };
+
+        match self.mode {
+            WinitEventSinkMode::Observe => Some(event),
+            WinitEventSinkMode::Route => None,
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_sink_get_id(sink: *mut ValueBox<EventSinkHandle>) -> usize {
+    sink.with_ref_ok(|sink| sink.id()).or_log(0)
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_event_sink_pop_event(
+    sink: *mut ValueBox<EventSinkHandle>,
+    window_id: *mut usize,
+    event_type: *mut WinitEventType,
+    event_ptr: *mut *mut c_void,
+) -> *mut ValueBox<WinitWindowEvent> {
+    sink.with_ref_ok(|sink| {
+        sink.pop_event()
+            .map(|window_event| {
+                unsafe {
+                    *window_id = window_event
+                        .window_id()
+                        .map(|id| id.into_raw())
+                        .unwrap_or(0);
+                    *event_type = window_event.event_type();
+                    *event_ptr = window_event.as_ptr();
+                };
+                value_box!(window_event).into_raw()
+            })
+            .unwrap_or_else(|| std::ptr::null_mut())
+    })
+    .or_log(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn winit_event_sink_release(sink: *mut ValueBox<EventSinkHandle>) {
+    sink.release();
+}
@@ -1,32 +1,228 @@
-use crate::{VirtualKeyCode, WindowHandle, WinitKeyLocation};
+use crate::{
+    ApplicationHandle, EventQueueWatchdog, VirtualKeyCode, WindowHandle, WinitKeyLocation,
+    WinitLatencyReport, WinitPointerEventMode, WinitResizeCause, WinitSyntheticKeyPolicy,
+    WinitTextEncoding, WinitTextSource,
+};
 use parking_lot::Mutex;
+use raw_window_handle_extensions::{VeryRawDisplayHandle, VeryRawWindowHandle};
+use std::any::Any;
 use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
+use std::ops::RangeInclusive;
 use std::os::raw::c_void;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use string_box::StringBox;
-use value_box::{ValueBox, ValueBoxPointer};
-use winit::dpi::{LogicalSize, PhysicalPosition};
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::{
-    ButtonSource, ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+    ButtonSource, DeviceEvent, DeviceId, ElementState, FingerId, Force, Ime, MouseButton,
+    MouseScrollDelta, PointerKind, PointerSource, TouchPhase, WindowEvent,
 };
-use winit::keyboard::{Key, KeyLocation, ModifiersKeyState};
-use winit::window::WindowId;
+use winit::keyboard::{Key, KeyLocation, ModifiersKeyState, ModifiersState, PhysicalKey};
+use winit::monitor::MonitorHandle;
+use winit::window::{Theme, WindowId};
 
 #[derive(Clone)]
-pub struct ApplicationEvents(Arc<Mutex<VecDeque<WinitWindowEvent>>>);
+pub struct ApplicationEvents(Arc<Mutex<EventQueue>>);
+
+/// How many of the latest queue latencies of input events are kept for the percentiles
+const QUEUE_LATENCY_SAMPLES: usize = 1024;
+
+struct EventQueue {
+    events: VecDeque<WinitWindowEvent>,
+    watchdog: Option<EventQueueWatchdog>,
+    coalesce_cursor_moves: bool,
+    /// In nanoseconds, the oldest samples are dropped first
+    queue_latencies: VecDeque<u64>,
+}
+
+impl EventQueue {
+    /// Remember how long the event waited in the queue
+    fn popped(&mut self, event: &mut WinitWindowEvent, now: Instant) {
+        event.queue_latency = event
+            .enqueued_at
+            .map(|enqueued_at| now.saturating_duration_since(enqueued_at));
+
+        if let Some(queue_latency) = event.queue_latency {
+            if event.event_type().is_input() {
+                if self.queue_latencies.len() == QUEUE_LATENCY_SAMPLES {
+                    self.queue_latencies.pop_front();
+                }
+                self.queue_latencies
+                    .push_back(queue_latency.as_nanos() as u64);
+            }
+        }
+    }
+
+    /// Replace the trailing cursor move of the same window with a newer one
+    fn coalesce(&mut self, event: WinitWindowEvent) -> Option<WinitWindowEvent> {
+        if !self.coalesce_cursor_moves
+            || event.event_type() != WinitEventType::WindowEventCursorMoved
+        {
+            return Some(event);
+        }
+
+        match self.events.back_mut() {
+            Some(last_event)
+                if last_event.event_type() == WinitEventType::WindowEventCursorMoved
+                    && last_event.window_id() == event.window_id() =>
+            {
+                *last_event = event;
+                None
+            }
+            _ => Some(event),
+        }
+    }
+}
 
 impl ApplicationEvents {
     pub fn new() -> Self {
-        Self(Arc::new(Mutex::new(VecDeque::new())))
+        Self(Arc::new(Mutex::new(EventQueue {
+            events: VecDeque::new(),
+            watchdog: None,
+            coalesce_cursor_moves: false,
+            queue_latencies: VecDeque::new(),
+        })))
+    }
+
+    /// Keep only the latest cursor position between pops instead of every intermediate move
+    pub fn set_coalesce_cursor_moves(&self, coalesce_cursor_moves: bool) {
+        self.0.lock().coalesce_cursor_moves = coalesce_cursor_moves;
     }
 
     pub fn pop_event(&self) -> Option<WinitWindowEvent> {
-        self.0.lock().pop_front()
+        let (event, notification) = {
+            let mut queue = self.0.lock();
+            let mut event = queue.events.pop_front();
+            if let Some(event) = event.as_mut() {
+                queue.popped(event, Instant::now());
+            }
+            let queue_length = queue.events.len();
+            let notification = queue
+                .watchdog
+                .as_mut()
+                .and_then(|watchdog| watchdog.check(queue_length));
+            (event, notification)
+        };
+
+        if let Some(notification) = notification {
+            notification.notify();
+        }
+        event
+    }
+
+    /// Window id and type of the next event, without removing it from the queue
+    pub fn peek_event(&self) -> Option<(Option<WindowId>, WinitEventType)> {
+        self.0
+            .lock()
+            .events
+            .front()
+            .map(|event| (event.window_id(), event.event_type()))
+    }
+
+    /// Events stamped when they were received keep the stamp, so that the time spent in the
+    /// event callback before an event reached this queue, possibly a sink, is also measured
+    pub fn push_event(&self, mut event: WinitWindowEvent) {
+        event.enqueued_at.get_or_insert_with(Instant::now);
+        let notification = {
+            let mut queue = self.0.lock();
+            let queue_length = queue.events.len();
+
+            let mut notification = None;
+            let mut should_drop = false;
+            if let Some(watchdog) = queue.watchdog.as_mut() {
+                notification = watchdog.check(queue_length);
+                should_drop = watchdog.should_drop(event.event_type());
+            }
+
+            if should_drop {
+                trace!("Dropping input event on a stalled queue: {:?}", &event);
+            } else if let Some(event) = queue.coalesce(event) {
+                queue.events.push_back(event);
+            }
+            notification
+        };
+
+        if let Some(notification) = notification {
+            notification.notify();
+        }
+    }
+
+    /// Take up to `count` events from the front of the queue at once
+    pub fn pop_events(&self, count: usize) -> VecDeque<WinitWindowEvent> {
+        let (events, notification) = {
+            let mut queue = self.0.lock();
+            let count = count.min(queue.events.len());
+            let mut events: VecDeque<_> = queue.events.drain(..count).collect();
+            let now = Instant::now();
+            for event in events.iter_mut() {
+                queue.popped(event, now);
+            }
+            let queue_length = queue.events.len();
+            let notification = queue
+                .watchdog
+                .as_mut()
+                .and_then(|watchdog| watchdog.check(queue_length));
+            (events, notification)
+        };
+
+        if let Some(notification) = notification {
+            notification.notify();
+        }
+        events
+    }
+
+    /// Approximate heap memory held by the pending events, in bytes
+    pub fn memory_usage(&self) -> usize {
+        let queue = self.0.lock();
+        let events: usize = queue
+            .events
+            .iter()
+            .map(|event| std::mem::size_of_val(event.event.as_ref()))
+            .sum();
+        queue.events.capacity() * std::mem::size_of::<WinitWindowEvent>() + events
+    }
+
+    /// Release the capacity the queue keeps after bursts of events
+    pub fn trim(&self) {
+        self.0.lock().events.shrink_to_fit();
+    }
+
+    /// Take all pending events at once leaving the queue empty
+    pub fn take_events(&self) -> VecDeque<WinitWindowEvent> {
+        let (events, notification) = {
+            let mut queue = self.0.lock();
+            let mut events = std::mem::take(&mut queue.events);
+            let now = Instant::now();
+            for event in events.iter_mut() {
+                queue.popped(event, now);
+            }
+            let notification = queue
+                .watchdog
+                .as_mut()
+                .and_then(|watchdog| watchdog.check(0));
+            (events, notification)
+        };
+
+        if let Some(notification) = notification {
+            notification.notify();
+        }
+        events
+    }
+
+    /// Percentiles of the time the latest input events waited in the queue, in nanoseconds
+    pub fn queue_latency(&self) -> WinitLatencyReport {
+        let mut samples: Vec<u64> = self.0.lock().queue_latencies.iter().copied().collect();
+        if samples.is_empty() {
+            return WinitLatencyReport::default();
+        }
+        WinitLatencyReport::from_samples(&mut samples)
     }
 
-    pub fn push_event(&self, event: WinitWindowEvent) {
-        self.0.lock().push_back(event);
+    pub fn set_watchdog(&self, watchdog: Option<EventQueueWatchdog>) {
+        self.0.lock().watchdog = watchdog;
     }
 }
 
@@ -36,7 +232,22 @@ impl Debug for ApplicationEvents {
     }
 }
 
+fn hovered_files(
+    paths: Vec<PathBuf>,
+    position: PhysicalPosition<f64>,
+    window: &WindowHandle,
+) -> Vec<Box<dyn WinitEvent>> {
+    let is_accepted = window.accepts_drop(WinitDropPayloadType::Files);
+    paths
+        .into_iter()
+        .map(|path| {
+            Box::new(WinitHoveredFileEvent::new(path, position, is_accepted)) as Box<dyn WinitEvent>
+        })
+        .collect()
+}
+
 pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn WinitEvent>> {
+    trace_span!("convert_event");
     match event {
         WindowEvent::SurfaceResized(size) => {
             let width = size.width;
@@ -48,7 +259,11 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 return vec![];
             }
 
-            let surface_resized_event = WinitWindowResizedEvent { width, height };
+            let surface_resized_event = WinitWindowResizedEvent {
+                width,
+                height,
+                cause: window.take_resize_cause(),
+            };
 
             vec![Box::new(surface_resized_event)]
         }
@@ -59,18 +274,58 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
         WindowEvent::CloseRequested => {
             vec![Box::new(WinitWindowCloseRequestedEvent)]
         }
-        WindowEvent::Destroyed => vec![],
+        WindowEvent::Destroyed => vec![Box::new(WinitWindowDestroyedEvent)],
         WindowEvent::Focused(focused) => {
             vec![Box::new(WinitWindowFocusedEvent {
                 is_focused: focused,
             })]
         }
+        WindowEvent::DragEntered { paths, position } => {
+            window.set_dragged_paths(paths.clone());
+            hovered_files(paths, position, window)
+        }
+        // winit does not repeat the paths while the drag moves over the window
+        WindowEvent::DragMoved { position } => {
+            hovered_files(window.dragged_paths(), position, window)
+        }
+        WindowEvent::DragDropped { paths, position } => {
+            window.set_dragged_paths(vec![]);
+            // the host did not declare files as accepted, so the drop is ignored and the hover
+            // ends, winit does not send a drag left after a drop
+            if !window.accepts_drop(WinitDropPayloadType::Files) {
+                return vec![Box::new(WinitHoveredFileCancelledEvent)];
+            }
+            if window.application_handle().settings().batch_dropped_files {
+                return vec![Box::new(WinitDroppedFilesEvent::new(paths, position))];
+            }
+            paths
+                .into_iter()
+                .map(|path| {
+                    Box::new(WinitDroppedFileEvent::new(path, position)) as Box<dyn WinitEvent>
+                })
+                .collect()
+        }
+        WindowEvent::DragLeft { .. } => {
+            window.set_dragged_paths(vec![]);
+            vec![Box::new(WinitHoveredFileCancelledEvent)]
+        }
+        WindowEvent::ThemeChanged(theme) => vec![Box::new(WinitThemeChangedEvent {
+            theme: theme.into(),
+        })],
         WindowEvent::KeyboardInput {
+            device_id,
             event,
             is_synthetic,
             ..
         } => {
+            let settings = window.application_handle().settings();
+            let synthetic_keys = settings.synthetic_keys;
+            if is_synthetic && synthetic_keys == WinitSyntheticKeyPolicy::Suppress {
+                return vec![];
+            }
+
             let mut keyboard_input = WinitEventKeyboardInput::default();
+            keyboard_input.device_id = window.application_handle().device_id(device_id);
             match event.state {
                 ElementState::Pressed => {
                     keyboard_input.state = WinitEventInputElementState::Pressed
@@ -80,6 +335,11 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 }
             };
 
+            let dead_key = match event.logical_key {
+                Key::Dead(character) => Some(WinitDeadKeyEvent::new(character)),
+                _ => None,
+            };
+
             let relevant_key = if event.location != KeyLocation::Numpad {
                 event.key_without_modifiers
             }
@@ -94,45 +354,74 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 }
                 Key::Character(ch) => {
                     keyboard_input.key_type = WinitKeyType::Character;
-                    keyboard_input.character_key =
-                        ValueBox::new(StringBox::from_string(ch.to_string())).into_raw();
+                    let mut chars = ch.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(character), None) => {
+                            keyboard_input.character_code_point = character as u32;
+                        }
+                        _ => {
+                            keyboard_input.character_key =
+                                ValueBox::new(StringBox::from_string(ch.to_string())).into_raw();
+                        }
+                    }
                 }
                 _ => {
                     keyboard_input.key_type = WinitKeyType::Unknown;
                 }
             }
 
+            keyboard_input.scan_code = physical_key_scan_code(event.physical_key);
             keyboard_input.key_location = WinitKeyLocation::from(event.location);
             keyboard_input.is_synthetic = is_synthetic;
+            keyboard_input.text = boxed_optional_string(event.text.as_deref());
+            keyboard_input.text_with_all_modifiers =
+                boxed_optional_string(event.text_with_all_modifiers.as_deref());
 
             let mut events = vec![Box::new(keyboard_input) as Box<dyn WinitEvent>];
 
-            if event.state == ElementState::Pressed {
-                if let Some(text) = event.text_with_all_modifiers {
-                    let text_event = WinitEventReceivedText {
-                        text: ValueBox::new(StringBox::from_string(text.to_string())).into_raw(),
-                    };
+            let produces_text = !is_synthetic || synthetic_keys == WinitSyntheticKeyPolicy::Deliver;
 
-                    events.push(Box::new(text_event) as Box<dyn WinitEvent>);
+            if event.state == ElementState::Pressed && produces_text {
+                if let Some(dead_key) = dead_key {
+                    events.push(Box::new(dead_key));
+                }
+                let text = match settings.text_source {
+                    WinitTextSource::TextWithAllModifiers => event.text_with_all_modifiers,
+                    WinitTextSource::Text => event.text,
+                };
+                if let Some(text) = text {
+                    events.push(received_text(text.to_string(), window));
                 }
             }
 
             events
         }
-        WindowEvent::Ime(Ime::Commit(string)) => {
-            let text_event = WinitEventReceivedText {
+        WindowEvent::Ime(Ime::Commit(string)) => vec![received_text(string, window)],
+        WindowEvent::Ime(Ime::Preedit(string, cursor)) => {
+            let (cursor_start, cursor_end) = cursor.unwrap_or_default();
+            let preedit_event = WinitEventImePreedit {
                 text: ValueBox::new(StringBox::from_string(string)).into_raw(),
+                has_cursor: cursor.is_some(),
+                cursor_start,
+                cursor_end,
             };
 
-            vec![Box::new(text_event)]
+            vec![Box::new(preedit_event)]
+        }
+        WindowEvent::ActivationTokenDone { token, .. } => {
+            vec![Box::new(WinitActivationTokenDoneEvent {
+                token: ValueBox::new(StringBox::from_string(token.into_raw())).into_raw(),
+            })]
         }
+        WindowEvent::Ime(Ime::Enabled) => vec![Box::new(WinitEventImeEnabled)],
+        WindowEvent::Ime(Ime::Disabled) => vec![Box::new(WinitEventImeDisabled)],
         WindowEvent::ModifiersChanged(modifiers) => {
             let modifiers_changed = WinitEventModifiersChanged {
                 shift: modifiers.state().shift_key(),
                 ctrl: modifiers.state().control_key(),
                 alt: modifiers.state().alt_key(),
                 logo: modifiers.state().meta_key(),
-                num_lock: false,
+                num_lock: window.application_handle().num_lock(),
                 left_shift: modifiers.lshift_state().into(),
                 right_shift: modifiers.rshift_state().into(),
                 left_ctrl: modifiers.lcontrol_state().into(),
@@ -142,61 +431,165 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 left_logo: modifiers.lsuper_state().into(),
                 right_logo: modifiers.rsuper_state().into(),
             };
+            window.set_modifiers(WinitModifiers::from(modifiers.state()));
+            window.application_handle().set_modifiers(modifiers_changed);
             vec![Box::new(modifiers_changed)]
         }
-        WindowEvent::PointerMoved { position, .. } => {
+        event @ (WindowEvent::PointerMoved { .. }
+        | WindowEvent::PointerEntered { .. }
+        | WindowEvent::PointerLeft { .. }
+        | WindowEvent::PointerButton { .. })
+            if window.application_handle().settings().pointer_events
+                == WinitPointerEventMode::Unified =>
+        {
+            convert_pointer_event(event, window)
+        }
+        WindowEvent::PointerMoved {
+            device_id,
+            position,
+            source,
+            ..
+        } => {
+            let device_id = window.application_handle().device_id(device_id);
+            if let PointerSource::Touch { finger_id, .. } = source {
+                return vec![Box::new(WinitTouchEvent::new(
+                    device_id,
+                    WinitEventTouchPhase::Moved,
+                    position,
+                    finger_id,
+                ))];
+            }
+
             let cursor_moved = WinitCursorMovedEvent {
-                device_id: 0,
+                device_id,
                 x: position.x,
                 y: position.y,
+                pointer_kind: WinitPointerKind::from(&source),
             };
 
             vec![Box::new(cursor_moved)]
         }
-        WindowEvent::PointerEntered { .. } => vec![],
-        WindowEvent::PointerLeft { .. } => vec![],
-        WindowEvent::MouseWheel { delta, phase, .. } => {
+        WindowEvent::PointerEntered {
+            kind: PointerKind::Touch(_),
+            ..
+        } => vec![],
+        WindowEvent::PointerEntered {
+            device_id,
+            position,
+            ..
+        } => vec![Box::new(WinitCursorEnteredEvent {
+            device_id: window.application_handle().device_id(device_id),
+            x: position.x,
+            y: position.y,
+        })],
+        WindowEvent::PointerLeft {
+            kind: PointerKind::Touch(_),
+            ..
+        } => vec![],
+        WindowEvent::PointerLeft { device_id, .. } => vec![Box::new(WinitCursorLeftEvent {
+            device_id: window.application_handle().device_id(device_id),
+        })],
+        WindowEvent::MouseWheel {
+            device_id,
+            delta,
+            phase,
+            ..
+        } => {
             let mut mouse_wheel_event = WinitMouseWheelEvent {
-                device_id: 0,
+                device_id: window.application_handle().device_id(device_id),
                 phase: Default::default(),
                 delta: Default::default(),
+                modifiers: window.modifiers(),
+                position: window.pointer_position().into(),
             };
 
-            match delta {
-                MouseScrollDelta::LineDelta(x, y) => {
-                    mouse_wheel_event.delta.delta_type = WinitEventMouseScrollDeltaType::LineDelta;
-                    mouse_wheel_event.delta.x = -x as f64;
-                    mouse_wheel_event.delta.y = y as f64;
-                }
+            let scroll_settings = window.application_handle().settings().scroll;
+
+            let (delta_type, x, y) = match delta {
+                MouseScrollDelta::LineDelta(x, y) => match scroll_settings.pixels_per_line {
+                    Some(pixels_per_line) => (
+                        WinitEventMouseScrollDeltaType::PixelDelta,
+                        x as f64 * pixels_per_line,
+                        y as f64 * pixels_per_line,
+                    ),
+                    None => (
+                        WinitEventMouseScrollDeltaType::LineDelta,
+                        x as f64,
+                        y as f64,
+                    ),
+                },
                 MouseScrollDelta::PixelDelta(PhysicalPosition { x, y }) => {
-                    mouse_wheel_event.delta.delta_type = WinitEventMouseScrollDeltaType::PixelDelta;
-                    mouse_wheel_event.delta.x = -x;
-                    mouse_wheel_event.delta.y = y.clone();
+                    (WinitEventMouseScrollDeltaType::PixelDelta, x, y)
                 }
-            }
+            };
 
-            match phase {
-                TouchPhase::Started => {
-                    mouse_wheel_event.phase = WinitEventTouchPhase::Started;
-                }
-                TouchPhase::Moved => {
-                    mouse_wheel_event.phase = WinitEventTouchPhase::Moved;
-                }
-                TouchPhase::Ended => {
-                    mouse_wheel_event.phase = WinitEventTouchPhase::Ended;
-                }
-                TouchPhase::Cancelled => {
-                    mouse_wheel_event.phase = WinitEventTouchPhase::Cancelled;
-                }
-            }
+            let (x, y) = scroll_settings.apply(x, y);
+            mouse_wheel_event.delta.delta_type = delta_type;
+            mouse_wheel_event.delta.x = x;
+            mouse_wheel_event.delta.y = y;
+
+            mouse_wheel_event.phase = phase.into();
 
             vec![Box::new(mouse_wheel_event)]
         }
-        WindowEvent::PointerButton { state, button, .. } => {
+        WindowEvent::PinchGesture {
+            device_id,
+            delta,
+            phase,
+            ..
+        } => {
+            vec![Box::new(WinitPinchGestureEvent {
+                device_id: window.application_handle().device_id(device_id),
+                phase: phase.into(),
+                delta,
+            })]
+        }
+        WindowEvent::PanGesture {
+            device_id,
+            delta,
+            phase,
+            ..
+        } => {
+            vec![Box::new(WinitPanGestureEvent {
+                device_id: window.application_handle().device_id(device_id),
+                phase: phase.into(),
+                x: delta.x as f64,
+                y: delta.y as f64,
+            })]
+        }
+        WindowEvent::DoubleTapGesture { device_id, .. } => {
+            vec![Box::new(WinitDoubleTapGestureEvent {
+                device_id: window.application_handle().device_id(device_id),
+            })]
+        }
+        // added after the event types were fixed, delivered as an extension event
+        WindowEvent::RotationGesture {
+            device_id,
+            delta,
+            phase,
+        } => {
+            vec![Box::new(WinitExtensionEvent::rotation_gesture(
+                window.application_handle().device_id(device_id),
+                delta,
+                phase.into(),
+            ))]
+        }
+        WindowEvent::PointerButton {
+            state,
+            position,
+            button,
+            device_id,
+            ..
+        } => {
+            let device_id = window.application_handle().device_id(device_id);
             let mut mouse_input_event = WinitMouseInputEvent {
-                device_id: 0,
+                device_id,
                 state: Default::default(),
                 button: Default::default(),
+                modifiers: window.modifiers(),
+                pointer_kind: WinitPointerKind::from(&button),
+                position: position.into(),
+                click_count: 0,
             };
 
             match state {
@@ -208,42 +601,18 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 }
             }
 
-            match button {
-                ButtonSource::Mouse(mouse_button) => match mouse_button {
-                    MouseButton::Left => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Left;
-                        mouse_input_event.button.button_code = 0;
-                    }
-                    MouseButton::Right => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Right;
-                        mouse_input_event.button.button_code = 1;
-                    }
-                    MouseButton::Middle => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Middle;
-                        mouse_input_event.button.button_code = 2;
-                    }
-                    MouseButton::Other(code) => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Other;
-                        mouse_input_event.button.button_code = code;
-                    }
-                    MouseButton::Back => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Back;
-                        mouse_input_event.button.button_code = 3;
-                    }
-                    MouseButton::Forward => {
-                        mouse_input_event.button.button_type = WinitEventMouseButtonType::Forward;
-                        mouse_input_event.button.button_code = 4;
-                    }
-                },
-                ButtonSource::Touch { .. } => {
-                    mouse_input_event.button.button_type = WinitEventMouseButtonType::Left;
-                    mouse_input_event.button.button_code = 0;
-                }
-                ButtonSource::Unknown(code) => {
-                    mouse_input_event.button.button_type = WinitEventMouseButtonType::Other;
-                    mouse_input_event.button.button_code = code;
-                }
+            if let ButtonSource::Touch { finger_id, .. } = button {
+                let phase = match state {
+                    ElementState::Pressed => WinitEventTouchPhase::Started,
+                    ElementState::Released => WinitEventTouchPhase::Ended,
+                };
+                return vec![Box::new(WinitTouchEvent::new(
+                    device_id, phase, position, finger_id,
+                ))];
             }
+            mouse_input_event.button = WinitEventMouseButton::from(&button);
+            mouse_input_event.click_count =
+                window.count_click(mouse_input_event.button, state, position);
 
             vec![Box::new(mouse_input_event)]
         }
@@ -251,9 +620,7 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
             scale_factor,
             mut surface_size_writer,
         } => {
-            let current_logical_size: LogicalSize<f64> =
-                window.surface_size().to_logical(window.scale_factor());
-            let new_physical_size = current_logical_size.to_physical(scale_factor);
+            let new_physical_size = window.scaled_surface_size(scale_factor);
 
             let scale_factor_changed = WinitWindowScaleFactorChangedEvent {
                 scale_factor,
@@ -261,16 +628,271 @@ pub fn convert_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn W
                 height: new_physical_size.height,
             };
 
+            window.expect_resize(WinitResizeCause::ScaleFactor, new_physical_size.into());
             let _ = surface_size_writer.request_surface_size(new_physical_size);
 
             vec![Box::new(scale_factor_changed)]
         }
-        WindowEvent::RedrawRequested => vec![],
+        WindowEvent::RedrawRequested => {
+            if window
+                .application_handle()
+                .settings()
+                .enqueue_redraw_requested
+            {
+                vec![Box::new(WinitWindowRedrawRequestedEvent)]
+            } else {
+                vec![]
+            }
+        }
+        _ => vec![],
+    }
+}
+
+/// Mouse, touch and pen input as a single stream of pointer events
+fn convert_pointer_event(event: WindowEvent, window: &WindowHandle) -> Vec<Box<dyn WinitEvent>> {
+    let (device_id, kind, phase, position, id, force) = match event {
+        WindowEvent::PointerEntered {
+            device_id,
+            position,
+            kind,
+            ..
+        } => {
+            let (kind, id) = pointer_kind_and_id(kind);
+            (
+                device_id,
+                kind,
+                WinitPointerPhase::Entered,
+                position,
+                id,
+                None,
+            )
+        }
+        WindowEvent::PointerLeft {
+            device_id, kind, ..
+        } => {
+            let (kind, id) = pointer_kind_and_id(kind);
+            let position = window.pointer_position();
+            (device_id, kind, WinitPointerPhase::Left, position, id, None)
+        }
+        WindowEvent::PointerMoved {
+            device_id,
+            position,
+            source,
+            ..
+        } => {
+            let kind = WinitPointerKind::from(&source);
+            let (id, force) = match source {
+                PointerSource::Touch { finger_id, force } => (finger_id.into_raw() as u64, force),
+                _ => (0, None),
+            };
+            (
+                device_id,
+                kind,
+                WinitPointerPhase::Moved,
+                position,
+                id,
+                force,
+            )
+        }
+        WindowEvent::PointerButton {
+            device_id,
+            state,
+            position,
+            button,
+            ..
+        } => {
+            let kind = WinitPointerKind::from(&button);
+            let mouse_button = WinitEventMouseButton::from(&button);
+            let phase = match state {
+                ElementState::Pressed => WinitPointerPhase::Pressed,
+                ElementState::Released => WinitPointerPhase::Released,
+            };
+            let (id, force) = match button {
+                ButtonSource::Touch { finger_id, force } => (finger_id.into_raw() as u64, force),
+                _ => (0, None),
+            };
+            let buttons = match (kind, state) {
+                (WinitPointerKind::Touch, ElementState::Pressed) => 1,
+                (WinitPointerKind::Touch, ElementState::Released) => 0,
+                _ => window.update_pressed_buttons(mouse_button, state),
+            };
+            return vec![Box::new(WinitPointerEvent {
+                device_id: window.application_handle().device_id(device_id),
+                kind,
+                phase,
+                position: position.into(),
+                button: mouse_button,
+                buttons,
+                pressure: pointer_pressure(buttons, force),
+                id,
+                modifiers: window.modifiers(),
+                click_count: window.count_click(mouse_button, state, position),
+            })];
+        }
+        _ => return vec![],
+    };
+
+    // a touch is in contact from the moment it enters the window until it leaves
+    let buttons = match (kind, phase) {
+        (WinitPointerKind::Touch, WinitPointerPhase::Left) => 0,
+        (WinitPointerKind::Touch, _) => 1,
+        _ => window.pressed_buttons(),
+    };
+    vec![Box::new(WinitPointerEvent {
+        device_id: window.application_handle().device_id(device_id),
+        kind,
+        phase,
+        position: position.into(),
+        button: Default::default(),
+        buttons,
+        pressure: pointer_pressure(buttons, force),
+        id,
+        modifiers: window.modifiers(),
+        click_count: 0,
+    })]
+}
+
+/// Touches are identified by their finger, the mouse is always 0
+fn pointer_kind_and_id(kind: PointerKind) -> (WinitPointerKind, u64) {
+    match kind {
+        PointerKind::Mouse => (WinitPointerKind::Mouse, 0),
+        PointerKind::Touch(finger_id) => (WinitPointerKind::Touch, finger_id.into_raw() as u64),
+        PointerKind::Unknown => (WinitPointerKind::Unknown, 0),
+    }
+}
+
+/// Devices without pressure sensing report 0.5 while in contact, like web pointer events
+fn pointer_pressure(buttons: u32, force: Option<Force>) -> f64 {
+    match force {
+        Some(force) => force.normalized(),
+        None if buttons != 0 => 0.5,
+        None => 0.0,
+    }
+}
+
+fn received_text(text: String, window: &WindowHandle) -> Box<dyn WinitEvent> {
+    match window.application_handle().settings().text_encoding {
+        WinitTextEncoding::Utf8 => Box::new(WinitEventReceivedText {
+            text: ValueBox::new(StringBox::from_string(text)).into_raw(),
+        }),
+        WinitTextEncoding::Utf32 => Box::new(WinitEventReceivedTextUtf32::new(
+            text.chars().map(|character| character as u32).collect(),
+        )),
+    }
+}
+
+/// The types of events a window event may be converted to.
+/// Events that update the window state when converted report all types,
+/// so that their conversion is never skipped.
+pub fn window_event_mask(event: &WindowEvent) -> u64 {
+    let types: &[WinitEventType] = match event {
+        WindowEvent::Moved(_) => &[WinitEventType::WindowEventMoved],
+        WindowEvent::CloseRequested => &[WinitEventType::WindowEventCloseRequested],
+        WindowEvent::Destroyed => &[WinitEventType::WindowEventDestroyed],
+        WindowEvent::Focused(_) => &[WinitEventType::WindowEventFocused],
+        WindowEvent::DragEntered { .. } | WindowEvent::DragMoved { .. } => {
+            &[WinitEventType::WindowEventHoveredFile]
+        }
+        WindowEvent::DragDropped { .. } => &[
+            WinitEventType::WindowEventDroppedFile,
+            WinitEventType::Winit30WindowEventDroppedFiles,
+        ],
+        WindowEvent::DragLeft { .. } => &[WinitEventType::WindowEventHoveredFileCancelled],
+        WindowEvent::ThemeChanged(_) => &[WinitEventType::Winit30WindowEventThemeChanged],
+        WindowEvent::KeyboardInput { .. } => &[
+            WinitEventType::Winit30WindowEventKeyboardInput,
+            WinitEventType::Winit30WindowEventDeadKey,
+            WinitEventType::Winit30WindowEventReceivedText,
+            WinitEventType::Winit30WindowEventReceivedTextUtf32,
+        ],
+        WindowEvent::Ime(Ime::Commit(_)) => &[
+            WinitEventType::Winit30WindowEventReceivedText,
+            WinitEventType::Winit30WindowEventReceivedTextUtf32,
+        ],
+        WindowEvent::Ime(Ime::Preedit(..)) => &[WinitEventType::Winit30WindowEventImePreedit],
+        WindowEvent::Ime(Ime::Enabled) => &[WinitEventType::Winit30WindowEventImeEnabled],
+        WindowEvent::Ime(Ime::Disabled) => &[WinitEventType::Winit30WindowEventImeDisabled],
+        WindowEvent::PointerMoved { .. } => &[
+            WinitEventType::WindowEventCursorMoved,
+            WinitEventType::WindowEventTouch,
+            WinitEventType::Winit30WindowEventPointer,
+        ],
+        WindowEvent::PointerEntered { .. } => &[
+            WinitEventType::WindowEventCursorEntered,
+            WinitEventType::Winit30WindowEventPointer,
+        ],
+        WindowEvent::PointerLeft { .. } => &[
+            WinitEventType::WindowEventCursorLeft,
+            WinitEventType::Winit30WindowEventPointer,
+        ],
+        WindowEvent::MouseWheel { .. } => &[WinitEventType::WindowEventMouseWheel],
+        WindowEvent::PointerButton { .. } => &[
+            WinitEventType::WindowEventMouseInput,
+            WinitEventType::WindowEventTouch,
+            WinitEventType::Winit30WindowEventPointer,
+        ],
+        WindowEvent::PinchGesture { .. } => &[WinitEventType::Winit30WindowEventPinchGesture],
+        WindowEvent::PanGesture { .. } => &[WinitEventType::Winit30WindowEventPanGesture],
+        WindowEvent::DoubleTapGesture { .. } => {
+            &[WinitEventType::Winit30WindowEventDoubleTapGesture]
+        }
+        WindowEvent::RotationGesture { .. } => &[WinitEventType::Winit30ExtensionEvent],
+        WindowEvent::RedrawRequested => &[WinitEventType::RedrawRequested],
+        WindowEvent::ActivationTokenDone { .. } => {
+            &[WinitEventType::Winit30WindowEventActivationTokenDone]
+        }
+        _ => return u64::MAX,
+    };
+
+    types
+        .iter()
+        .fold(0, |mask, event_type| mask | event_type.mask())
+}
+
+pub fn convert_device_event(
+    device_id: Option<DeviceId>,
+    event: DeviceEvent,
+    application_handle: &ApplicationHandle,
+) -> Vec<Box<dyn WinitEvent>> {
+    trace_span!("convert_device_event");
+    let device_id = application_handle.device_id(device_id);
+    let settings = application_handle.settings();
+
+    match event {
+        DeviceEvent::PointerMotion { delta: (dx, dy) } if settings.device_pointer_motion => {
+            vec![Box::new(WinitDeviceMouseMotionEvent { device_id, dx, dy })]
+        }
+        DeviceEvent::Button { button, state } if settings.raw_device_input => {
+            vec![Box::new(WinitDeviceButtonEvent {
+                device_id,
+                button,
+                state: state.into(),
+            })]
+        }
+        DeviceEvent::Key(event) if settings.raw_device_input => {
+            vec![Box::new(WinitDeviceKeyEvent {
+                device_id,
+                scan_code: physical_key_scan_code(event.physical_key),
+                state: event.state.into(),
+            })]
+        }
         _ => vec![],
     }
 }
 
-#[derive(Debug, Default)]
+#[cfg(any(windows_platform, macos_platform, x11_platform, wayland_platform))]
+pub fn physical_key_scan_code(physical_key: PhysicalKey) -> u32 {
+    use winit::platform::scancode::PhysicalKeyExtScancode;
+    physical_key.to_scancode().unwrap_or(0)
+}
+
+#[cfg(not(any(windows_platform, macos_platform, x11_platform, wayland_platform)))]
+pub fn physical_key_scan_code(_physical_key: PhysicalKey) -> u32 {
+    0
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitWindowCloseRequestedEvent;
 
@@ -280,7 +902,30 @@ impl WinitEvent for WinitWindowCloseRequestedEvent {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowRedrawRequestedEvent;
+
+impl WinitEvent for WinitWindowRedrawRequestedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::RedrawRequested
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowDestroyedEvent;
+
+impl WinitEvent for WinitWindowDestroyedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventDestroyed
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitTouchEvent {
     device_id: i64,
@@ -291,12 +936,39 @@ pub struct WinitTouchEvent {
     id: u64,
 }
 
-#[derive(Debug, Default)]
+impl WinitTouchEvent {
+    fn new(
+        device_id: i64,
+        phase: WinitEventTouchPhase,
+        position: PhysicalPosition<f64>,
+        finger_id: FingerId,
+    ) -> Self {
+        Self {
+            device_id,
+            phase,
+            x: position.x,
+            y: position.y,
+            id: finger_id.into_raw() as u64,
+        }
+    }
+}
+
+impl WinitEvent for WinitTouchEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventTouch
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitMouseWheelEvent {
     device_id: i64,
     phase: WinitEventTouchPhase,
     delta: WinitMouseScrollDelta,
+    modifiers: WinitModifiers,
+    /// Where the pointer was last seen within the window
+    position: WinitPointerPosition,
 }
 
 impl WinitEvent for WinitMouseWheelEvent {
@@ -305,163 +977,1310 @@ impl WinitEvent for WinitMouseWheelEvent {
     }
 }
 
+/// A two-finger pinch on a trackpad. Positive delta values indicate magnification (zooming in)
+/// and negative - shrinking (zooming out).
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitMouseInputEvent {
+pub struct WinitPinchGestureEvent {
     device_id: i64,
-    state: WinitEventInputElementState,
-    button: WinitEventMouseButton,
+    phase: WinitEventTouchPhase,
+    delta: f64,
 }
 
-impl WinitEvent for WinitMouseInputEvent {
+impl WinitEvent for WinitPinchGestureEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventMouseInput
+        WinitEventType::Winit30WindowEventPinchGesture
     }
 }
 
+/// A two-finger pan on a trackpad, delivered separately from the mouse wheel so that
+/// inertial panning can be told apart from wheel ticks. The delta is in physical pixels.
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitCursorMovedEvent {
+pub struct WinitPanGestureEvent {
     device_id: i64,
+    phase: WinitEventTouchPhase,
     x: f64,
     y: f64,
 }
 
-impl WinitEvent for WinitCursorMovedEvent {
+impl WinitEvent for WinitPanGestureEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventCursorMoved
+        WinitEventType::Winit30WindowEventPanGesture
     }
 }
 
+/// Raw relative mouse motion as reported by the device, not affected by the cursor
+/// acceleration or by the window bounds. Used for pointer-lock style interactions.
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitWindowResizedEvent {
-    width: u32,
-    height: u32,
+pub struct WinitDeviceMouseMotionEvent {
+    device_id: i64,
+    dx: f64,
+    dy: f64,
 }
 
-impl WinitEvent for WinitWindowResizedEvent {
+impl WinitEvent for WinitDeviceMouseMotionEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventResized
+        WinitEventType::Winit30DeviceEventMouseMotion
     }
 }
 
+/// A raw button press of a device, delivered regardless of the window focus
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitWindowScaleFactorChangedEvent {
-    scale_factor: f64,
-    width: u32,
-    height: u32,
+pub struct WinitDeviceButtonEvent {
+    device_id: i64,
+    button: u32,
+    state: WinitEventInputElementState,
 }
 
-impl WinitEvent for WinitWindowScaleFactorChangedEvent {
+impl WinitEvent for WinitDeviceButtonEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventScaleFactorChanged
+        WinitEventType::Winit30DeviceEventButton
     }
 }
 
+/// A raw key press of a device identified by its platform scan code,
+/// delivered regardless of the window focus
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitWindowMovedEvent {
-    x: i32,
-    y: i32,
+pub struct WinitDeviceKeyEvent {
+    device_id: i64,
+    scan_code: u32,
+    state: WinitEventInputElementState,
 }
 
-impl WinitEvent for WinitWindowMovedEvent {
+impl WinitEvent for WinitDeviceKeyEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventMoved
+        WinitEventType::Winit30DeviceEventKey
     }
 }
 
+/// A double tap with two fingers on a trackpad, the "smart magnification" gesture on macOS
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitWindowFocusedEvent {
-    is_focused: bool,
+pub struct WinitDoubleTapGestureEvent {
+    device_id: i64,
 }
 
-impl WinitEvent for WinitWindowFocusedEvent {
+impl WinitEvent for WinitDoubleTapGestureEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::WindowEventFocused
+        WinitEventType::Winit30WindowEventDoubleTapGesture
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitEventKeyboardInput {
+pub struct WinitMouseInputEvent {
     device_id: i64,
-    scan_code: u32,
     state: WinitEventInputElementState,
-    key_type: WinitKeyType,
-    key_location: WinitKeyLocation,
-    named_key: VirtualKeyCode,
-    character_key: *mut ValueBox<StringBox>,
-    is_synthetic: bool,
+    button: WinitEventMouseButton,
+    modifiers: WinitModifiers,
+    pointer_kind: WinitPointerKind,
+    position: WinitPointerPosition,
+    /// The number of the click in a series of quick clicks, 1 for a single click.
+    /// Releases have the count of their press, 0 when click counting is disabled
+    click_count: u32,
 }
 
-impl WinitEvent for WinitEventKeyboardInput {
-    fn event_type(&self) -> WinitEventType {
-        WinitEventType::Winit30WindowEventKeyboardInput
-    }
+/// Mouse, touch or pen input delivered in the unified pointer event mode
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitPointerEvent {
+    device_id: i64,
+    kind: WinitPointerKind,
+    phase: WinitPointerPhase,
+    position: WinitPointerPosition,
+    /// The button that was pressed or released, unknown in the other phases
+    button: WinitEventMouseButton,
+    /// Mask of the buttons held down after the event with a bit for each button code.
+    /// A touch in contact is the left button
+    buttons: u32,
+    /// Normalized between 0 and 1
+    pressure: f64,
+    /// Tells simultaneous touches apart, 0 for the mouse
+    id: u64,
+    modifiers: WinitModifiers,
+    /// The number of the click in a series of quick clicks for the pressed and released
+    /// phases, 0 when click counting is disabled
+    click_count: u32,
 }
 
-impl Default for WinitEventKeyboardInput {
-    fn default() -> Self {
-        WinitEventKeyboardInput {
-            device_id: Default::default(),
-            scan_code: Default::default(),
-            state: Default::default(),
-            key_type: Default::default(),
-            key_location: WinitKeyLocation::Standard,
-            named_key: VirtualKeyCode::Unknown,
-            character_key: std::ptr::null_mut(),
-            is_synthetic: false,
-        }
+impl WinitEvent for WinitPointerEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventPointer
     }
 }
 
-impl Drop for WinitEventKeyboardInput {
-    fn drop(&mut self) {
+/// The position of the pointer in physical pixels relative to the window
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitPointerPosition {
+    x: f64,
+    y: f64,
+}
+
+impl From<PhysicalPosition<f64>> for WinitPointerPosition {
+    fn from(position: PhysicalPosition<f64>) -> Self {
+        Self {
+            x: position.x,
+            y: position.y,
+        }
+    }
+}
+
+impl WinitEvent for WinitMouseInputEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventMouseInput
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitCursorMovedEvent {
+    device_id: i64,
+    x: f64,
+    y: f64,
+    pointer_kind: WinitPointerKind,
+}
+
+impl WinitEvent for WinitCursorMovedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventCursorMoved
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitCursorEnteredEvent {
+    device_id: i64,
+    /// Where the pointer entered the window
+    x: f64,
+    y: f64,
+}
+
+impl WinitEvent for WinitCursorEnteredEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventCursorEntered
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitCursorLeftEvent {
+    device_id: i64,
+}
+
+impl WinitEvent for WinitCursorLeftEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventCursorLeft
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowResizedEvent {
+    width: u32,
+    height: u32,
+    cause: WinitResizeCause,
+}
+
+impl WinitEvent for WinitWindowResizedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventResized
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowScaleFactorChangedEvent {
+    scale_factor: f64,
+    width: u32,
+    height: u32,
+}
+
+impl WinitEvent for WinitWindowScaleFactorChangedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventScaleFactorChanged
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowMovedEvent {
+    x: i32,
+    y: i32,
+}
+
+impl WinitEvent for WinitWindowMovedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventMoved
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitWindowState {
+    Normal,
+    Maximized,
+    Minimized,
+    Fullscreen,
+}
+
+impl Default for WinitWindowState {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// The window was maximized, minimized, made fullscreen or restored, sent after the resize
+/// or occlusion event that revealed the change
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowStateChangedEvent {
+    previous_state: WinitWindowState,
+    state: WinitWindowState,
+}
+
+impl WinitWindowStateChangedEvent {
+    pub fn new(previous_state: WinitWindowState, state: WinitWindowState) -> Self {
+        Self {
+            previous_state,
+            state,
+        }
+    }
+}
+
+impl WinitEvent for WinitWindowStateChangedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventStateChanged
+    }
+}
+
+/// The window moved onto a different monitor, the host may need to lay out and rasterize
+/// its content for the scale factor of the new monitor. The size of the monitor is in
+/// physical pixels and is 0 if the platform does not report its video mode.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowMonitorChangedEvent {
+    scale_factor: f64,
+    width: u32,
+    height: u32,
+}
+
+impl WinitWindowMonitorChangedEvent {
+    pub fn new(monitor: &MonitorHandle) -> Self {
+        let size = monitor
+            .current_video_mode()
+            .map(|video_mode| video_mode.size())
+            .unwrap_or_default();
+        Self {
+            scale_factor: monitor.scale_factor(),
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+impl WinitEvent for WinitWindowMonitorChangedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventMonitorChanged
+    }
+}
+
+/// The kind of a host callback measured against the callback budget
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitCallbackKind {
+    FunctionCall,
+    Redraw,
+    Resize,
+    Focus,
+    CloseRequested,
+    ScaleFactor,
+}
+
+impl Default for WinitCallbackKind {
+    fn default() -> Self {
+        Self::FunctionCall
+    }
+}
+
+/// A host callback stalled the UI thread for longer than the callback budget.
+/// Listener callbacks are reported for their window, function calls for no window.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitCallbackOverBudgetEvent {
+    kind: WinitCallbackKind,
+    /// The token the thunk of the callback was registered with, 0 if it was not registered
+    token: usize,
+    duration_micros: u64,
+    budget_micros: u64,
+}
+
+impl WinitCallbackOverBudgetEvent {
+    pub fn new(
+        kind: WinitCallbackKind,
+        token: usize,
+        duration: Duration,
+        budget: Duration,
+    ) -> Self {
+        Self {
+            kind,
+            token,
+            duration_micros: duration.as_micros() as u64,
+            budget_micros: budget.as_micros() as u64,
+        }
+    }
+}
+
+impl WinitEvent for WinitCallbackOverBudgetEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30CallbackOverBudget
+    }
+}
+
+/// The window was entirely off-screen after the monitors changed and was moved onto the
+/// nearest monitor. The moved event follows once the platform applies the position.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowRepositionedEvent {
+    from_x: i32,
+    from_y: i32,
+    x: i32,
+    y: i32,
+}
+
+impl WinitWindowRepositionedEvent {
+    pub fn new(from: PhysicalPosition<i32>, to: PhysicalPosition<i32>) -> Self {
+        Self {
+            from_x: from.x,
+            from_y: from.y,
+            x: to.x,
+            y: to.y,
+        }
+    }
+}
+
+impl WinitEvent for WinitWindowRepositionedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventRepositioned
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowFocusedEvent {
+    is_focused: bool,
+}
+
+impl WinitEvent for WinitWindowFocusedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventFocused
+    }
+}
+
+/// A window action that can fail without the host waiting for its result
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitActionType {
+    RequestWindowSurfaceSize,
+    ApplyWindowConfig,
+    RecreateWindow,
+    FocusWindow,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitActionFailure {
+    /// The window was closed before the action reached the UI thread
+    WindowClosed,
+}
+
+/// An action addressed to a window was dropped, so that the host can stop waiting for
+/// the work that depends on it
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitActionFailedEvent {
+    action: WinitActionType,
+    window_id: usize,
+    reason: WinitActionFailure,
+}
+
+impl WinitActionFailedEvent {
+    pub fn new(action: WinitActionType, window_id: WindowId, reason: WinitActionFailure) -> Self {
+        Self {
+            action,
+            window_id: window_id.into_raw(),
+            reason,
+        }
+    }
+}
+
+impl WinitEvent for WinitActionFailedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventActionFailed
+    }
+}
+
+/// The native window was replaced by a new one, see `winit_window_handle_recreate`.
+/// The host must rebuild its rendering surface from the new raw handles.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitWindowRecreatedEvent {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    window_handle: VeryRawWindowHandle,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    display_handle: VeryRawDisplayHandle,
+    scale_factor: f64,
+    width: u32,
+    height: u32,
+}
+
+impl WinitWindowRecreatedEvent {
+    pub fn new(
+        window_handle: VeryRawWindowHandle,
+        display_handle: VeryRawDisplayHandle,
+        scale_factor: f64,
+        surface_size: PhysicalSize<u32>,
+    ) -> Self {
+        Self {
+            window_handle,
+            display_handle,
+            scale_factor,
+            width: surface_size.width,
+            height: surface_size.height,
+        }
+    }
+}
+
+impl WinitEvent for WinitWindowRecreatedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventRecreated
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitThemeChangedEvent {
+    theme: WinitTheme,
+}
+
+impl WinitEvent for WinitThemeChangedEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventThemeChanged
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitTheme {
+    Light,
+    Dark,
+}
+
+impl Default for WinitTheme {
+    fn default() -> Self {
+        WinitTheme::Light
+    }
+}
+
+impl From<Theme> for WinitTheme {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Light => WinitTheme::Light,
+            Theme::Dark => WinitTheme::Dark,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitEventKeyboardInput {
+    device_id: i64,
+    scan_code: u32,
+    state: WinitEventInputElementState,
+    key_type: WinitKeyType,
+    key_location: WinitKeyLocation,
+    named_key: VirtualKeyCode,
+    /// The character of a character key made of several code points, null otherwise
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    character_key: *mut ValueBox<StringBox>,
+    is_synthetic: bool,
+    /// The text produced by the key press, null if there is none
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    text: *mut ValueBox<StringBox>,
+    /// The text with all modifiers applied, null if there is none
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    text_with_all_modifiers: *mut ValueBox<StringBox>,
+    /// The character of a character key that is a single code point, so that most key presses
+    /// do not allocate a string. 0 when the character is in the character key string instead
+    character_code_point: u32,
+}
+
+unsafe impl Send for WinitEventKeyboardInput {}
+unsafe impl Sync for WinitEventKeyboardInput {}
+
+impl Debug for WinitEventKeyboardInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitEventKeyboardInput")
+            .field("device_id", &self.device_id)
+            .field("scan_code", &self.scan_code)
+            .field("state", &self.state)
+            .field("key_type", &self.key_type)
+            .field("key_location", &self.key_location)
+            .field("named_key", &self.named_key)
+            .field("character_key", &boxed_string(self.character_key))
+            .field("character_code_point", &self.character_code_point)
+            .field("is_synthetic", &self.is_synthetic)
+            .field("text", &boxed_string(self.text))
+            .field(
+                "text_with_all_modifiers",
+                &boxed_string(self.text_with_all_modifiers),
+            )
+            .finish()
+    }
+}
+
+impl WinitEvent for WinitEventKeyboardInput {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventKeyboardInput
+    }
+}
+
+impl Default for WinitEventKeyboardInput {
+    fn default() -> Self {
+        WinitEventKeyboardInput {
+            device_id: Default::default(),
+            scan_code: Default::default(),
+            state: Default::default(),
+            key_type: Default::default(),
+            key_location: WinitKeyLocation::Standard,
+            named_key: VirtualKeyCode::Unknown,
+            character_key: std::ptr::null_mut(),
+            is_synthetic: false,
+            text: std::ptr::null_mut(),
+            text_with_all_modifiers: std::ptr::null_mut(),
+            character_code_point: 0,
+        }
+    }
+}
+
+impl Clone for WinitEventKeyboardInput {
+    fn clone(&self) -> Self {
+        Self {
+            device_id: self.device_id,
+            scan_code: self.scan_code,
+            state: self.state,
+            key_type: self.key_type,
+            key_location: self.key_location,
+            named_key: self.named_key,
+            character_key: clone_boxed_string(self.character_key),
+            is_synthetic: self.is_synthetic,
+            text: clone_boxed_string(self.text),
+            text_with_all_modifiers: clone_boxed_string(self.text_with_all_modifiers),
+            character_code_point: self.character_code_point,
+        }
+    }
+}
+
+impl Drop for WinitEventKeyboardInput {
+    fn drop(&mut self) {
         if !self.character_key.is_null() {
             self.character_key.release();
         }
+        if !self.text.is_null() {
+            self.text.release();
+        }
+        if !self.text_with_all_modifiers.is_null() {
+            self.text_with_all_modifiers.release();
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitKeyType {
+    Unknown,
+    Named,
+    Character,
+}
+
+impl Default for WinitKeyType {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitEventReceivedText {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    text: *mut ValueBox<StringBox>,
+}
+
+unsafe impl Send for WinitEventReceivedText {}
+unsafe impl Sync for WinitEventReceivedText {}
+
+impl Debug for WinitEventReceivedText {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitEventReceivedText")
+            .field("text", &boxed_string(self.text))
+            .finish()
+    }
+}
+
+impl Clone for WinitEventReceivedText {
+    fn clone(&self) -> Self {
+        Self {
+            text: clone_boxed_string(self.text),
+        }
+    }
+}
+
+impl Drop for WinitEventReceivedText {
+    fn drop(&mut self) {
+        if !self.text.is_null() {
+            self.text.release();
+        }
+    }
+}
+
+impl WinitEvent for WinitEventReceivedText {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventReceivedText
+    }
+}
+
+/// Received text as UTF-32 code points, sent instead of [`WinitEventReceivedText`]
+/// when the text encoding of the application is [`WinitTextEncoding::Utf32`].
+/// The event owns the code points.
+#[repr(C)]
+pub struct WinitEventReceivedTextUtf32 {
+    code_points: *mut u32,
+    count: usize,
+}
+
+unsafe impl Send for WinitEventReceivedTextUtf32 {}
+unsafe impl Sync for WinitEventReceivedTextUtf32 {}
+
+impl WinitEventReceivedTextUtf32 {
+    pub fn new(code_points: Vec<u32>) -> Self {
+        let count = code_points.len();
+        let code_points = Box::into_raw(code_points.into_boxed_slice()) as *mut u32;
+        Self { code_points, count }
+    }
+
+    pub fn code_points(&self) -> &[u32] {
+        unsafe { std::slice::from_raw_parts(self.code_points, self.count) }
+    }
+
+    pub fn text(&self) -> String {
+        self.code_points()
+            .iter()
+            .map(|code_point| char::from_u32(*code_point).unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+}
+
+impl Debug for WinitEventReceivedTextUtf32 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitEventReceivedTextUtf32")
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+impl Clone for WinitEventReceivedTextUtf32 {
+    fn clone(&self) -> Self {
+        Self::new(self.code_points().to_vec())
+    }
+}
+
+impl Drop for WinitEventReceivedTextUtf32 {
+    fn drop(&mut self) {
+        drop(unsafe {
+            Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                self.code_points,
+                self.count,
+            ))
+        });
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WinitEventReceivedTextUtf32 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut event = serializer.serialize_struct("WinitEventReceivedTextUtf32", 1)?;
+        event.serialize_field("text", &self.text())?;
+        event.end()
+    }
+}
+
+impl WinitEvent for WinitEventReceivedTextUtf32 {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventReceivedTextUtf32
+    }
+}
+
+/// Bytes owned by an event, laid out as a pointer followed by the length so that the host
+/// can read them in place. Shared by the events that carry an arbitrary payload.
+#[repr(C)]
+pub struct WinitEventPayload {
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for WinitEventPayload {}
+unsafe impl Sync for WinitEventPayload {}
+
+impl WinitEventPayload {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let ptr = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { ptr, len }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Debug for WinitEventPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitEventPayload")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Clone for WinitEventPayload {
+    fn clone(&self) -> Self {
+        Self::new(self.as_slice().to_vec())
+    }
+}
+
+impl Drop for WinitEventPayload {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.ptr, self.len)) });
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WinitEventPayload {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(self.as_slice(), serializer)
+    }
+}
+
+/// A message posted by a host thread to a window, delivered through the event queue in order
+/// with the events of the platform. The event owns a copy of the payload.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitUserEvent {
+    /// Chosen by the host to tell messages apart
+    tag: u64,
+    payload: WinitEventPayload,
+}
+
+impl WinitUserEvent {
+    pub fn new(tag: u64, payload: Vec<u8>) -> Self {
+        Self {
+            tag,
+            payload: WinitEventPayload::new(payload),
+        }
+    }
+
+    pub fn tag(&self) -> u64 {
+        self.tag
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        self.payload.as_slice()
+    }
+}
+
+impl WinitEvent for WinitUserEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30UserEvent
+    }
+}
+
+/// Extension ids assigned by this library to events added after a host was built.
+/// They are all delivered as `Winit30ExtensionEvent`, so hosts skip the ids they do not know
+/// instead of failing on an unknown event type.
+pub const WINIT_EXTENSION_EVENT_IDS: RangeInclusive<u32> = 1..=0xFFFF;
+
+/// A rotation gesture on a trackpad (macOS, iOS). The payload is laid out as a C struct
+/// `{ int64_t device_id; float delta; uint32_t phase; }` in the native byte order,
+/// the delta is given in degrees, counterclockwise, and the phase is a [`WinitEventTouchPhase`]
+pub const WINIT_EXTENSION_EVENT_ROTATION_GESTURE: u32 = 1;
+
+/// An event that does not have its own event type, identified by its extension id.
+/// The layout of the payload is defined by the extension, the event owns it.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitExtensionEvent {
+    extension_id: u32,
+    payload: WinitEventPayload,
+}
+
+impl WinitExtensionEvent {
+    pub fn new(extension_id: u32, payload: Vec<u8>) -> Self {
+        debug_assert!(
+            WINIT_EXTENSION_EVENT_IDS.contains(&extension_id),
+            "Extension id {} is not reserved for extension events",
+            extension_id
+        );
+        Self {
+            extension_id,
+            payload: WinitEventPayload::new(payload),
+        }
+    }
+
+    pub fn rotation_gesture(device_id: i64, delta: f32, phase: WinitEventTouchPhase) -> Self {
+        let mut payload = Vec::with_capacity(16);
+        payload.extend(device_id.to_ne_bytes());
+        payload.extend(delta.to_ne_bytes());
+        payload.extend((phase as u32).to_ne_bytes());
+        Self::new(WINIT_EXTENSION_EVENT_ROTATION_GESTURE, payload)
+    }
+
+    pub fn extension_id(&self) -> u32 {
+        self.extension_id
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        self.payload.as_slice()
+    }
+}
+
+impl WinitEvent for WinitExtensionEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30ExtensionEvent
+    }
+}
+
+/// The first extension id reserved for extension events, see [`WinitExtensionEvent`]
+#[no_mangle]
+pub extern "C" fn winit_extension_event_ids_first() -> u32 {
+    *WINIT_EXTENSION_EVENT_IDS.start()
+}
+
+/// The last extension id reserved for extension events, see [`WinitExtensionEvent`]
+#[no_mangle]
+pub extern "C" fn winit_extension_event_ids_last() -> u32 {
+    *WINIT_EXTENSION_EVENT_IDS.end()
+}
+
+/// A dead key was pressed and starts a composition, the combined character arrives later as
+/// received text. The pending character (for example "´") can be shown as a composition hint.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitDeadKeyEvent {
+    /// False if the platform does not know which character the dead key produces
+    has_character: bool,
+    /// The pending character as a UTF-32 code point
+    character: u32,
+}
+
+impl WinitDeadKeyEvent {
+    pub fn new(character: Option<char>) -> Self {
+        Self {
+            has_character: character.is_some(),
+            character: character
+                .map(|character| character as u32)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl WinitEvent for WinitDeadKeyEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventDeadKey
+    }
+}
+
+/// An activation token requested with `winit_window_handle_request_activation_token`.
+/// Pass it to a launched child process (`XDG_ACTIVATION_TOKEN`) so it is allowed to take focus.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitActivationTokenDoneEvent {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    token: *mut ValueBox<StringBox>,
+}
+
+unsafe impl Send for WinitActivationTokenDoneEvent {}
+unsafe impl Sync for WinitActivationTokenDoneEvent {}
+
+impl Debug for WinitActivationTokenDoneEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitActivationTokenDoneEvent")
+            .field("token", &boxed_string(self.token))
+            .finish()
+    }
+}
+
+impl Clone for WinitActivationTokenDoneEvent {
+    fn clone(&self) -> Self {
+        Self {
+            token: clone_boxed_string(self.token),
+        }
+    }
+}
+
+impl Drop for WinitActivationTokenDoneEvent {
+    fn drop(&mut self) {
+        if !self.token.is_null() {
+            self.token.release();
+        }
+    }
+}
+
+impl WinitEvent for WinitActivationTokenDoneEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventActivationTokenDone
+    }
+}
+
+/// The text being composed by the input method, it is not yet committed.
+/// The cursor is a byte range within the preedit text, it is only set
+/// when `has_cursor` is true.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitEventImePreedit {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    text: *mut ValueBox<StringBox>,
+    has_cursor: bool,
+    cursor_start: usize,
+    cursor_end: usize,
+}
+
+unsafe impl Send for WinitEventImePreedit {}
+unsafe impl Sync for WinitEventImePreedit {}
+
+impl Debug for WinitEventImePreedit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitEventImePreedit")
+            .field("text", &boxed_string(self.text))
+            .field("has_cursor", &self.has_cursor)
+            .field("cursor_start", &self.cursor_start)
+            .field("cursor_end", &self.cursor_end)
+            .finish()
+    }
+}
+
+impl Clone for WinitEventImePreedit {
+    fn clone(&self) -> Self {
+        Self {
+            text: clone_boxed_string(self.text),
+            has_cursor: self.has_cursor,
+            cursor_start: self.cursor_start,
+            cursor_end: self.cursor_end,
+        }
+    }
+}
+
+impl Drop for WinitEventImePreedit {
+    fn drop(&mut self) {
+        if !self.text.is_null() {
+            self.text.release();
+        }
+    }
+}
+
+impl WinitEvent for WinitEventImePreedit {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventImePreedit
+    }
+}
+
+/// Kinds of content that can be dragged onto a window, combined into a mask of the types a
+/// window accepts. Winit only reports dragged files for now, so text and URIs are never
+/// hovered or dropped, even when accepted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitDropPayloadType {
+    Files,
+    Text,
+    Uris,
+}
+
+impl WinitDropPayloadType {
+    pub fn mask(&self) -> u32 {
+        1 << (*self as u32)
+    }
+}
+
+/// Sent for every dragged file when a drag enters or moves over the window, before anything
+/// is dropped, so that the host can tell whether the drop would be accepted
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitHoveredFileEvent {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    path: *mut ValueBox<StringBox>,
+    x: f64,
+    y: f64,
+    /// Mask of the [`WinitDropPayloadType`] of the dragged content
+    payload_types: u32,
+    /// The window accepts the dragged content
+    is_accepted: bool,
+}
+
+unsafe impl Send for WinitHoveredFileEvent {}
+unsafe impl Sync for WinitHoveredFileEvent {}
+
+impl WinitHoveredFileEvent {
+    fn new(path: PathBuf, position: PhysicalPosition<f64>, is_accepted: bool) -> Self {
+        Self {
+            path: boxed_path(path),
+            x: position.x,
+            y: position.y,
+            payload_types: WinitDropPayloadType::Files.mask(),
+            is_accepted,
+        }
+    }
+}
+
+impl Debug for WinitHoveredFileEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitHoveredFileEvent")
+            .field("path", &boxed_string(self.path))
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("payload_types", &self.payload_types)
+            .field("is_accepted", &self.is_accepted)
+            .finish()
+    }
+}
+
+impl Clone for WinitHoveredFileEvent {
+    fn clone(&self) -> Self {
+        Self {
+            path: clone_boxed_string(self.path),
+            x: self.x,
+            y: self.y,
+            payload_types: self.payload_types,
+            is_accepted: self.is_accepted,
+        }
+    }
+}
+
+impl Drop for WinitHoveredFileEvent {
+    fn drop(&mut self) {
+        if !self.path.is_null() {
+            self.path.release();
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-#[repr(u8)]
-pub enum WinitKeyType {
-    Unknown,
-    Named,
-    Character,
+impl WinitEvent for WinitHoveredFileEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventHoveredFile
+    }
 }
 
-impl Default for WinitKeyType {
-    fn default() -> Self {
-        Self::Unknown
+/// Sent for every file dropped onto a window that accepts files
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitDroppedFileEvent {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box"))]
+    path: *mut ValueBox<StringBox>,
+    x: f64,
+    y: f64,
+}
+
+unsafe impl Send for WinitDroppedFileEvent {}
+unsafe impl Sync for WinitDroppedFileEvent {}
+
+impl WinitDroppedFileEvent {
+    fn new(path: PathBuf, position: PhysicalPosition<f64>) -> Self {
+        Self {
+            path: boxed_path(path),
+            x: position.x,
+            y: position.y,
+        }
     }
 }
 
-#[derive(Debug)]
+impl Debug for WinitDroppedFileEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitDroppedFileEvent")
+            .field("path", &boxed_string(self.path))
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .finish()
+    }
+}
+
+impl Clone for WinitDroppedFileEvent {
+    fn clone(&self) -> Self {
+        Self {
+            path: clone_boxed_string(self.path),
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+impl Drop for WinitDroppedFileEvent {
+    fn drop(&mut self) {
+        if !self.path.is_null() {
+            self.path.release();
+        }
+    }
+}
+
+impl WinitEvent for WinitDroppedFileEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventDroppedFile
+    }
+}
+
+/// Sent once for all files dropped together onto a window that accepts files,
+/// instead of a dropped file event for each of them when dropped files are batched
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
-pub struct WinitEventReceivedText {
-    text: *mut ValueBox<StringBox>,
+pub struct WinitDroppedFilesEvent {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_string_box_list"))]
+    paths: *mut ValueBox<Vec<StringBox>>,
+    x: f64,
+    y: f64,
 }
 
-impl Drop for WinitEventReceivedText {
+unsafe impl Send for WinitDroppedFilesEvent {}
+unsafe impl Sync for WinitDroppedFilesEvent {}
+
+impl WinitDroppedFilesEvent {
+    fn new(paths: Vec<PathBuf>, position: PhysicalPosition<f64>) -> Self {
+        let paths = paths
+            .into_iter()
+            .map(|path| StringBox::from_string(path.to_string_lossy().into_owned()))
+            .collect();
+        Self {
+            paths: ValueBox::new(paths).into_raw(),
+            x: position.x,
+            y: position.y,
+        }
+    }
+
+    fn paths(&self) -> Vec<String> {
+        self.paths
+            .with_ref_ok(|paths| paths.iter().map(|path| path.to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Debug for WinitDroppedFilesEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WinitDroppedFilesEvent")
+            .field("paths", &self.paths())
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .finish()
+    }
+}
+
+impl Clone for WinitDroppedFilesEvent {
+    fn clone(&self) -> Self {
+        let paths = self
+            .paths()
+            .into_iter()
+            .map(StringBox::from_string)
+            .collect();
+        Self {
+            paths: ValueBox::new(paths).into_raw(),
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+impl Drop for WinitDroppedFilesEvent {
     fn drop(&mut self) {
-        if !self.text.is_null() {
-            self.text.release();
+        if !self.paths.is_null() {
+            self.paths.release();
         }
     }
 }
 
-impl WinitEvent for WinitEventReceivedText {
+impl WinitEvent for WinitDroppedFilesEvent {
     fn event_type(&self) -> WinitEventType {
-        WinitEventType::Winit30WindowEventReceivedText
+        WinitEventType::Winit30WindowEventDroppedFiles
+    }
+}
+
+/// The drag left the window or was cancelled without dropping anything
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitHoveredFileCancelledEvent;
+
+impl WinitEvent for WinitHoveredFileCancelledEvent {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::WindowEventHoveredFileCancelled
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitEventImeEnabled;
+
+impl WinitEvent for WinitEventImeEnabled {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventImeEnabled
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitEventImeDisabled;
+
+impl WinitEvent for WinitEventImeDisabled {
+    fn event_type(&self) -> WinitEventType {
+        WinitEventType::Winit30WindowEventImeDisabled
     }
 }
 
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitMouseScrollDelta {
     delta_type: WinitEventMouseScrollDeltaType,
@@ -470,6 +2289,7 @@ pub struct WinitMouseScrollDelta {
 }
 
 #[derive(Default, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitEventModifiersChanged {
     /// The "shift" key
@@ -494,7 +2314,36 @@ pub struct WinitEventModifiersChanged {
     right_logo: WinitModifierKeyState,
 }
 
+/// A snapshot of the modifiers state attached to pointer events
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C)]
+pub struct WinitModifiers {
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+    logo: bool,
+}
+
+impl WinitEventModifiersChanged {
+    pub fn with_num_lock(self, num_lock: bool) -> Self {
+        Self { num_lock, ..self }
+    }
+}
+
+impl From<ModifiersState> for WinitModifiers {
+    fn from(state: ModifiersState) -> Self {
+        Self {
+            shift: state.shift_key(),
+            ctrl: state.control_key(),
+            alt: state.alt_key(),
+            logo: state.meta_key(),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum WinitModifierKeyState {
     Unknown,
@@ -522,14 +2371,101 @@ impl WinitEvent for WinitEventModifiersChanged {
     }
 }
 
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C)]
 pub struct WinitEventMouseButton {
     button_type: WinitEventMouseButtonType,
     button_code: u16,
 }
 
-#[derive(Debug, Copy, Clone)]
+/// The kind of device that moves the pointer or presses its buttons.
+/// Touches are delivered as touch events unless pointer events are unified,
+/// pens are reported once winit tells them apart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitPointerKind {
+    Unknown,
+    Mouse,
+    Touch,
+    Pen,
+}
+
+impl Default for WinitPointerKind {
+    fn default() -> Self {
+        WinitPointerKind::Unknown
+    }
+}
+
+/// The step of the pointer interaction reported by a pointer event
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitPointerPhase {
+    Entered,
+    Moved,
+    Pressed,
+    Released,
+    Left,
+}
+
+impl Default for WinitPointerPhase {
+    fn default() -> Self {
+        WinitPointerPhase::Moved
+    }
+}
+
+impl From<&PointerSource> for WinitPointerKind {
+    fn from(source: &PointerSource) -> Self {
+        match source {
+            PointerSource::Mouse => WinitPointerKind::Mouse,
+            PointerSource::Touch { .. } => WinitPointerKind::Touch,
+            PointerSource::Unknown => WinitPointerKind::Unknown,
+        }
+    }
+}
+
+impl From<&ButtonSource> for WinitPointerKind {
+    fn from(button: &ButtonSource) -> Self {
+        match button {
+            ButtonSource::Mouse(_) => WinitPointerKind::Mouse,
+            ButtonSource::Touch { .. } => WinitPointerKind::Touch,
+            ButtonSource::Unknown(_) => WinitPointerKind::Unknown,
+        }
+    }
+}
+
+impl WinitEventMouseButton {
+    pub fn code(&self) -> u16 {
+        self.button_code
+    }
+}
+
+impl From<&ButtonSource> for WinitEventMouseButton {
+    /// A touch contact acts as the left button
+    fn from(button: &ButtonSource) -> Self {
+        let (button_type, button_code) = match button {
+            ButtonSource::Mouse(mouse_button) => match mouse_button {
+                MouseButton::Left => (WinitEventMouseButtonType::Left, 0),
+                MouseButton::Right => (WinitEventMouseButtonType::Right, 1),
+                MouseButton::Middle => (WinitEventMouseButtonType::Middle, 2),
+                MouseButton::Back => (WinitEventMouseButtonType::Back, 3),
+                MouseButton::Forward => (WinitEventMouseButtonType::Forward, 4),
+                MouseButton::Other(code) => (WinitEventMouseButtonType::Other, *code),
+            },
+            ButtonSource::Touch { .. } => (WinitEventMouseButtonType::Left, 0),
+            ButtonSource::Unknown(code) => (WinitEventMouseButtonType::Other, *code),
+        };
+        Self {
+            button_type,
+            button_code,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum WinitEventMouseButtonType {
     Unknown,
@@ -547,31 +2483,187 @@ impl Default for WinitEventMouseButtonType {
     }
 }
 
-pub trait WinitEvent: Debug {
+/// Read the contents of a boxed string owned by an event, used when printing events
+fn boxed_string(string: *mut ValueBox<StringBox>) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    string.with_ref_ok(|string| string.to_string()).ok()
+}
+
+fn boxed_path(path: PathBuf) -> *mut ValueBox<StringBox> {
+    ValueBox::new(StringBox::from_string(path.to_string_lossy().into_owned())).into_raw()
+}
+
+fn boxed_optional_string(string: Option<&str>) -> *mut ValueBox<StringBox> {
+    string
+        .map(|string| ValueBox::new(StringBox::from_string(string.to_string())).into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Events that own strings give the copy its own string box
+fn clone_boxed_string(string: *mut ValueBox<StringBox>) -> *mut ValueBox<StringBox> {
+    boxed_string(string)
+        .map(|string| ValueBox::new(StringBox::from_string(string)).into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Events are enqueued on the UI thread and popped by the host from any thread
+pub trait WinitEvent: Debug + Send + AsAny + CloneEvent + SerializeEvent {
     fn event_type(&self) -> WinitEventType;
 }
 
+pub trait CloneEvent {
+    fn clone_event(&self) -> Box<dyn WinitEvent>;
+}
+
+impl<T: WinitEvent + Clone + 'static> CloneEvent for T {
+    fn clone_event(&self) -> Box<dyn WinitEvent> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait SerializeEvent {
+    fn to_json(&self) -> serde_json::Value;
+}
+
+#[cfg(feature = "serde")]
+impl<T: WinitEvent + serde::Serialize> SerializeEvent for T {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|error| {
+            error!("Failed to serialize {:?}: {}", self, error);
+            serde_json::Value::Null
+        })
+    }
+}
+
+/// Events are only serializable with the `serde` feature
+#[cfg(not(feature = "serde"))]
+pub trait SerializeEvent {}
+
+#[cfg(not(feature = "serde"))]
+impl<T: WinitEvent> SerializeEvent for T {}
+
+#[cfg(feature = "serde")]
+fn serialize_string_box_list<S: serde::Serializer>(
+    list: &*mut ValueBox<Vec<StringBox>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let list = list
+        .with_ref_ok(|list| {
+            list.iter()
+                .map(|string| string.to_string())
+                .collect::<Vec<_>>()
+        })
+        .ok();
+    serializer.serialize_some(&list)
+}
+
+#[cfg(feature = "serde")]
+fn serialize_string_box<S: serde::Serializer>(
+    string: &*mut ValueBox<StringBox>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let string = string.with_ref_ok(|string| string.to_string()).ok();
+    serializer.serialize_some(&string)
+}
+
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct WinitWindowEvent {
-    pub window_id: WindowId,
+    /// Device events are not associated with any window
+    pub window_id: Option<WindowId>,
+    /// Time at which the event was received, see [`crate::EventClock`]
+    pub timestamp: u64,
     pub event: Box<dyn WinitEvent>,
+    /// Set when the event is received or else when it is pushed to a queue
+    pub enqueued_at: Option<Instant>,
+    /// How long the event waited in the queue, set when it is popped
+    pub queue_latency: Option<Duration>,
 }
 
 impl WinitWindowEvent {
-    pub fn window_id(&self) -> WindowId {
+    pub fn window_id(&self) -> Option<WindowId> {
         self.window_id
     }
 
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
     pub fn event_type(&self) -> WinitEventType {
         self.event.event_type()
     }
 
+    pub fn queue_latency(&self) -> Option<Duration> {
+        self.queue_latency
+    }
+
     pub fn as_ptr(&self) -> *mut c_void {
         self.event.as_ref() as *const _ as *mut c_void
     }
+
+    /// A deep copy of the event, for example to deliver it to more than one event sink
+    pub fn clone_event(&self) -> Self {
+        Self {
+            window_id: self.window_id,
+            timestamp: self.timestamp,
+            event: self.event.clone_event(),
+            enqueued_at: self.enqueued_at,
+            queue_latency: self.queue_latency,
+        }
+    }
+
+    /// The window id, timestamp, type and all fields of the event
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "window_id": self.window_id.map(|id| id.into_raw()),
+            "timestamp": self.timestamp,
+            "type": self.event_type(),
+            "event": self.event.to_json(),
+        })
+    }
+
+    pub fn downcast_ref<T: WinitEvent + 'static>(&self) -> Option<&T> {
+        self.event.as_ref().as_any().downcast_ref::<T>()
+    }
+}
+
+/// An event popped as part of a batch. The event box is owned by the host and must be
+/// released, the event pointer is valid as long as the box is not released.
+#[derive(Debug)]
+#[repr(C)]
+pub struct WinitEventRecord {
+    pub window_id: usize,
+    pub event_type: WinitEventType,
+    pub event_ptr: *mut c_void,
+    pub event: *mut ValueBox<WinitWindowEvent>,
+}
+
+impl From<WinitWindowEvent> for WinitEventRecord {
+    fn from(event: WinitWindowEvent) -> Self {
+        Self {
+            window_id: event.window_id().map(|id| id.into_raw()).unwrap_or(0),
+            event_type: event.event_type(),
+            event_ptr: event.as_ptr(),
+            event: value_box!(event).into_raw(),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum WinitEventType {
     Unknown,
@@ -606,6 +2698,69 @@ pub enum WinitEventType {
     Winit30WindowEventModifiersChanged,
     Winit30WindowEventKeyboardInput,
     Winit30WindowEventReceivedText,
+    Winit30WindowEventImePreedit,
+    Winit30WindowEventImeEnabled,
+    Winit30WindowEventImeDisabled,
+    Winit30WindowEventPinchGesture,
+    Winit30WindowEventPanGesture,
+    Winit30WindowEventDoubleTapGesture,
+    Winit30WindowEventThemeChanged,
+    Winit30DeviceEventMouseMotion,
+    Winit30DeviceEventButton,
+    Winit30DeviceEventKey,
+    Winit30WindowEventActivationTokenDone,
+    Winit30WindowEventRecreated,
+    Winit30WindowEventReceivedTextUtf32,
+    Winit30WindowEventDeadKey,
+    Winit30WindowEventActionFailed,
+    Winit30UserEvent,
+    Winit30WindowEventRepositioned,
+    Winit30WindowEventStateChanged,
+    Winit30CallbackOverBudget,
+    Winit30WindowEventMonitorChanged,
+    Winit30WindowEventPointer,
+    Winit30WindowEventDroppedFiles,
+    /// Shared by all extension events, see [`WINIT_EXTENSION_EVENT_IDS`]
+    Winit30ExtensionEvent,
+}
+
+impl WinitEventType {
+    /// A bit identifying the event type within an event mask
+    pub fn mask(&self) -> u64 {
+        1 << (*self as u32)
+    }
+
+    /// Return true if the event is produced by the user input, as opposed to window lifecycle
+    /// events such as resize, focus or close requests
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            WinitEventType::WindowEventReceivedCharacter
+                | WinitEventType::WindowEventKeyboardInput
+                | WinitEventType::WindowEventCursorMoved
+                | WinitEventType::WindowEventCursorEntered
+                | WinitEventType::WindowEventCursorLeft
+                | WinitEventType::WindowEventMouseWheel
+                | WinitEventType::WindowEventMouseInput
+                | WinitEventType::WindowEventTouchpadPressure
+                | WinitEventType::WindowEventAxisMotion
+                | WinitEventType::WindowEventTouch
+                | WinitEventType::Winit30WindowEventPointer
+                | WinitEventType::ModifiersChanged
+                | WinitEventType::Winit30WindowEventModifiersChanged
+                | WinitEventType::Winit30WindowEventKeyboardInput
+                | WinitEventType::Winit30WindowEventReceivedText
+                | WinitEventType::Winit30WindowEventReceivedTextUtf32
+                | WinitEventType::Winit30WindowEventDeadKey
+                | WinitEventType::Winit30WindowEventImePreedit
+                | WinitEventType::Winit30WindowEventPinchGesture
+                | WinitEventType::Winit30WindowEventPanGesture
+                | WinitEventType::Winit30WindowEventDoubleTapGesture
+                | WinitEventType::Winit30DeviceEventMouseMotion
+                | WinitEventType::Winit30DeviceEventButton
+                | WinitEventType::Winit30DeviceEventKey
+        )
+    }
 }
 
 impl Default for WinitEventType {
@@ -615,6 +2770,7 @@ impl Default for WinitEventType {
 }
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum WinitEventTouchPhase {
     Unknown,
@@ -624,6 +2780,17 @@ pub enum WinitEventTouchPhase {
     Cancelled,
 }
 
+impl From<TouchPhase> for WinitEventTouchPhase {
+    fn from(phase: TouchPhase) -> Self {
+        match phase {
+            TouchPhase::Started => WinitEventTouchPhase::Started,
+            TouchPhase::Moved => WinitEventTouchPhase::Moved,
+            TouchPhase::Ended => WinitEventTouchPhase::Ended,
+            TouchPhase::Cancelled => WinitEventTouchPhase::Cancelled,
+        }
+    }
+}
+
 impl Default for WinitEventTouchPhase {
     fn default() -> Self {
         WinitEventTouchPhase::Unknown
@@ -631,6 +2798,7 @@ impl Default for WinitEventTouchPhase {
 }
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum WinitEventMouseScrollDeltaType {
     Unknown,
@@ -645,6 +2813,7 @@ impl Default for WinitEventMouseScrollDeltaType {
 }
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum WinitEventInputElementState {
     Unknown,
@@ -658,7 +2827,392 @@ impl Default for WinitEventInputElementState {
     }
 }
 
+impl From<ElementState> for WinitEventInputElementState {
+    fn from(state: ElementState) -> Self {
+        match state {
+            ElementState::Pressed => WinitEventInputElementState::Pressed,
+            ElementState::Released => WinitEventInputElementState::Released,
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_event_release(event: *mut ValueBox<WinitWindowEvent>) {
     event.release();
 }
+
+/// Render the event as a json object with its window id, timestamp, type and all fields,
+/// so that event streams can be logged and compared across platforms.
+/// Falls back to the debug representation when built without the `serde` feature.
+#[no_mangle]
+pub extern "C" fn winit_window_event_to_string(
+    event: *mut ValueBox<WinitWindowEvent>,
+    string: *mut ValueBox<StringBox>,
+) {
+    event
+        .with_ref(|event| {
+            #[cfg(feature = "serde")]
+            let rendered = event.to_json().to_string();
+            #[cfg(not(feature = "serde"))]
+            let rendered = format!("{:?}", event);
+            string.with_mut_ok(|string| string.set_string(rendered))
+        })
+        .log();
+}
+
+/// Render the debug representation of the event, including its window id and all fields.
+#[no_mangle]
+pub extern "C" fn winit_window_event_to_debug_string(
+    event: *mut ValueBox<WinitWindowEvent>,
+    string: *mut ValueBox<StringBox>,
+) {
+    event
+        .with_ref(|event| string.with_mut_ok(|string| string.set_string(format!("{:?}", event))))
+        .log();
+}
+
+/// Returns 0 for device events that are not associated with any window
+#[no_mangle]
+pub extern "C" fn winit_window_event_get_window_id(
+    event: *mut ValueBox<WinitWindowEvent>,
+) -> usize {
+    event
+        .with_ref_ok(|event| event.window_id().map(|id| id.into_raw()).unwrap_or(0))
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_event_get_timestamp(event: *mut ValueBox<WinitWindowEvent>) -> u64 {
+    event.with_ref_ok(|event| event.timestamp()).or_log(0)
+}
+
+/// How long the event waited from when it was received until it was popped from the main queue
+/// or the queue of a sink, in nanoseconds
+#[no_mangle]
+pub extern "C" fn winit_window_event_get_queue_latency(
+    event: *mut ValueBox<WinitWindowEvent>,
+) -> u64 {
+    event
+        .with_ref_ok(|event| queue_latency_nanos(event))
+        .or_log(0)
+}
+
+/// 0 for events that were never queued
+pub fn queue_latency_nanos(event: &WinitWindowEvent) -> u64 {
+    event
+        .queue_latency()
+        .map(|queue_latency| queue_latency.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_event_get_event_type(
+    event: *mut ValueBox<WinitWindowEvent>,
+) -> WinitEventType {
+    event
+        .with_ref_ok(|event| event.event_type())
+        .or_log(WinitEventType::Unknown)
+}
+
+/// Define a function that returns a pointer to the typed event struct,
+/// or null if the boxed event is of a different type.
+/// The pointer is valid as long as the boxed event is not released.
+macro_rules! event_downcast {
+    ($function_name:ident, $event_type:ty) => {
+        #[no_mangle]
+        pub extern "C" fn $function_name(
+            event: *mut ValueBox<WinitWindowEvent>,
+        ) -> *const $event_type {
+            event
+                .with_ref_ok(|event| {
+                    event
+                        .downcast_ref::<$event_type>()
+                        .map(|event| event as *const $event_type)
+                        .unwrap_or(std::ptr::null())
+                })
+                .or_log(std::ptr::null())
+        }
+    };
+}
+
+event_downcast!(winit_window_event_as_resized, WinitWindowResizedEvent);
+event_downcast!(winit_window_event_as_moved, WinitWindowMovedEvent);
+event_downcast!(winit_window_event_as_focused, WinitWindowFocusedEvent);
+event_downcast!(winit_window_event_as_theme_changed, WinitThemeChangedEvent);
+event_downcast!(
+    winit_window_event_as_scale_factor_changed,
+    WinitWindowScaleFactorChangedEvent
+);
+event_downcast!(
+    winit_window_event_as_keyboard_input,
+    WinitEventKeyboardInput
+);
+event_downcast!(winit_window_event_as_received_text, WinitEventReceivedText);
+event_downcast!(
+    winit_window_event_as_modifiers_changed,
+    WinitEventModifiersChanged
+);
+event_downcast!(winit_window_event_as_ime_preedit, WinitEventImePreedit);
+event_downcast!(winit_window_event_as_cursor_moved, WinitCursorMovedEvent);
+event_downcast!(
+    winit_window_event_as_cursor_entered,
+    WinitCursorEnteredEvent
+);
+event_downcast!(winit_window_event_as_cursor_left, WinitCursorLeftEvent);
+event_downcast!(winit_window_event_as_mouse_input, WinitMouseInputEvent);
+event_downcast!(winit_window_event_as_mouse_wheel, WinitMouseWheelEvent);
+event_downcast!(winit_window_event_as_touch, WinitTouchEvent);
+event_downcast!(winit_window_event_as_pinch_gesture, WinitPinchGestureEvent);
+event_downcast!(winit_window_event_as_pan_gesture, WinitPanGestureEvent);
+event_downcast!(
+    winit_window_event_as_double_tap_gesture,
+    WinitDoubleTapGestureEvent
+);
+event_downcast!(
+    winit_window_event_as_device_mouse_motion,
+    WinitDeviceMouseMotionEvent
+);
+event_downcast!(winit_window_event_as_device_button, WinitDeviceButtonEvent);
+event_downcast!(winit_window_event_as_device_key, WinitDeviceKeyEvent);
+event_downcast!(
+    winit_window_event_as_activation_token_done,
+    WinitActivationTokenDoneEvent
+);
+event_downcast!(
+    winit_window_event_as_window_recreated,
+    WinitWindowRecreatedEvent
+);
+event_downcast!(
+    winit_window_event_as_received_text_utf32,
+    WinitEventReceivedTextUtf32
+);
+event_downcast!(winit_window_event_as_dead_key, WinitDeadKeyEvent);
+event_downcast!(winit_window_event_as_action_failed, WinitActionFailedEvent);
+event_downcast!(winit_window_event_as_hovered_file, WinitHoveredFileEvent);
+event_downcast!(winit_window_event_as_dropped_file, WinitDroppedFileEvent);
+event_downcast!(winit_window_event_as_user_event, WinitUserEvent);
+event_downcast!(
+    winit_window_event_as_repositioned,
+    WinitWindowRepositionedEvent
+);
+event_downcast!(
+    winit_window_event_as_state_changed,
+    WinitWindowStateChangedEvent
+);
+event_downcast!(
+    winit_window_event_as_callback_over_budget,
+    WinitCallbackOverBudgetEvent
+);
+event_downcast!(
+    winit_window_event_as_monitor_changed,
+    WinitWindowMonitorChangedEvent
+);
+event_downcast!(winit_window_event_as_pointer, WinitPointerEvent);
+event_downcast!(winit_window_event_as_dropped_files, WinitDroppedFilesEvent);
+event_downcast!(winit_window_event_as_extension, WinitExtensionEvent);
+
+/// The number of strings in a list owned by an event, such as the paths of dropped files
+#[no_mangle]
+pub extern "C" fn winit_string_box_list_len(list: *mut ValueBox<Vec<StringBox>>) -> usize {
+    list.with_ref_ok(|list| list.len()).or_log(0)
+}
+
+/// Copy the string at the index into a new string box owned by the caller,
+/// null if the index is out of bounds
+#[no_mangle]
+pub extern "C" fn winit_string_box_list_at(
+    list: *mut ValueBox<Vec<StringBox>>,
+    index: usize,
+) -> *mut ValueBox<StringBox> {
+    list.with_ref_ok(|list| {
+        list.get(index)
+            .map(|string| ValueBox::new(StringBox::from_string(string.to_string())).into_raw())
+            .unwrap_or(std::ptr::null_mut())
+    })
+    .or_log(std::ptr::null_mut())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor_moved(x: f64) -> WinitWindowEvent {
+        WinitWindowEvent {
+            window_id: None,
+            timestamp: 0,
+            event: Box::new(WinitCursorMovedEvent {
+                device_id: 0,
+                x,
+                y: 0.0,
+                pointer_kind: WinitPointerKind::Mouse,
+            }),
+            enqueued_at: None,
+            queue_latency: None,
+        }
+    }
+
+    #[test]
+    fn coalesces_trailing_cursor_moves() {
+        let events = ApplicationEvents::new();
+        events.set_coalesce_cursor_moves(true);
+
+        events.push_event(cursor_moved(1.0));
+        events.push_event(cursor_moved(2.0));
+        events.push_event(WinitWindowEvent {
+            window_id: None,
+            timestamp: 0,
+            event: Box::new(WinitWindowFocusedEvent { is_focused: true }),
+            enqueued_at: None,
+            queue_latency: None,
+        });
+        events.push_event(cursor_moved(3.0));
+        events.push_event(cursor_moved(4.0));
+
+        let events = events.take_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0]
+                .downcast_ref::<WinitCursorMovedEvent>()
+                .map(|event| event.x),
+            Some(2.0)
+        );
+        assert_eq!(
+            events[2]
+                .downcast_ref::<WinitCursorMovedEvent>()
+                .map(|event| event.x),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn measures_queue_latency_of_popped_events() {
+        let events = ApplicationEvents::new();
+        assert_eq!(events.queue_latency().count(), 0);
+
+        events.push_event(cursor_moved(1.0));
+        events.push_event(cursor_moved(2.0));
+        let popped = events.pop_event().unwrap();
+        assert!(popped.queue_latency().is_some());
+        assert!(cursor_moved(3.0).queue_latency().is_none());
+
+        events.take_events();
+        assert_eq!(events.queue_latency().count(), 2);
+    }
+
+    #[test]
+    fn measures_queue_latency_from_when_the_event_was_received() {
+        let events = ApplicationEvents::new();
+        let received_at = Instant::now() - Duration::from_millis(50);
+
+        events.push_event(WinitWindowEvent {
+            enqueued_at: Some(received_at),
+            ..cursor_moved(1.0)
+        });
+        let popped = events.pop_event().unwrap();
+
+        assert!(popped.queue_latency().unwrap() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn trims_the_drained_queue() {
+        let events = ApplicationEvents::new();
+        for x in 0..100 {
+            events.push_event(cursor_moved(x as f64));
+        }
+        assert!(events.memory_usage() > 100 * std::mem::size_of::<WinitCursorMovedEvent>());
+
+        events.pop_events(100);
+        events.trim();
+        assert_eq!(events.memory_usage(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn renders_events_as_json() {
+        let json = cursor_moved(1.0).to_json();
+
+        assert_eq!(json["type"], "WindowEventCursorMoved");
+        assert_eq!(json["window_id"], serde_json::Value::Null);
+        assert_eq!(json["event"]["x"], 1.0);
+    }
+
+    /// Clones the event and checks the copy once the original released its data
+    fn clone_case<T: Clone + 'static>(
+        event: T,
+        check: impl FnOnce(&T) + 'static,
+    ) -> Box<dyn FnOnce()> {
+        Box::new(move || {
+            let copy = event.clone();
+            drop(event);
+            check(&copy);
+        })
+    }
+
+    #[test]
+    fn clones_events_owning_their_data() {
+        let cases = [
+            clone_case(
+                WinitDroppedFilesEvent::new(
+                    vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")],
+                    PhysicalPosition::new(1.0, 2.0),
+                ),
+                |files| {
+                    assert_eq!(
+                        files.paths(),
+                        vec!["a.txt".to_string(), "b.txt".to_string()]
+                    );
+                    assert_eq!(winit_string_box_list_len(files.paths), 2);
+                    assert!(winit_string_box_list_at(files.paths, 2).is_null());
+                },
+            ),
+            clone_case(
+                WinitEventReceivedTextUtf32::new("a€😀".chars().map(|c| c as u32).collect()),
+                |text| {
+                    assert_eq!(text.code_points(), &[0x61, 0x20AC, 0x1F600]);
+                    assert_eq!(text.text(), "a€😀");
+                },
+            ),
+            clone_case(WinitUserEvent::new(7, vec![1, 2, 3]), |user| {
+                assert_eq!(user.tag(), 7);
+                assert_eq!(user.payload(), &[1, 2, 3]);
+            }),
+            clone_case(WinitUserEvent::new(0, vec![]), |user| {
+                assert!(user.payload().is_empty());
+            }),
+            clone_case(WinitExtensionEvent::new(42, vec![4, 5]), |extension| {
+                assert_eq!(extension.extension_id(), 42);
+                assert_eq!(extension.payload(), &[4, 5]);
+                assert_eq!(
+                    extension.event_type(),
+                    WinitEventType::Winit30ExtensionEvent
+                );
+            }),
+        ];
+
+        for case in cases {
+            case();
+        }
+    }
+
+    #[test]
+    fn encodes_rotation_gestures_as_extension_events() {
+        let event = WinitExtensionEvent::rotation_gesture(3, 1.5, WinitEventTouchPhase::Moved);
+        let payload = event.payload();
+
+        assert_eq!(event.extension_id(), WINIT_EXTENSION_EVENT_ROTATION_GESTURE);
+        assert_eq!(payload.len(), 16);
+        assert_eq!(i64::from_ne_bytes(payload[0..8].try_into().unwrap()), 3);
+        assert_eq!(f32::from_ne_bytes(payload[8..12].try_into().unwrap()), 1.5);
+        assert_eq!(
+            u32::from_ne_bytes(payload[12..16].try_into().unwrap()),
+            WinitEventTouchPhase::Moved as u32
+        );
+    }
+
+    #[test]
+    fn presses_pointers_without_force_halfway() {
+        assert_eq!(pointer_pressure(0, None), 0.0);
+        assert_eq!(pointer_pressure(0b100, None), 0.5);
+        assert_eq!(pointer_pressure(1, Some(Force::Normalized(0.25))), 0.25);
+    }
+}
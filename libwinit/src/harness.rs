@@ -0,0 +1,126 @@
+use crate::{
+    ApplicationAction, ApplicationBuilder, ApplicationHandle, InjectWindowEventAction,
+    WindowHandle, WinitEventType, WinitWindowEvent,
+};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use winit::event::WindowEvent;
+use winit::window::WindowAttributes;
+
+/// How long the harness waits for the event loop before giving up
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs the real event loop on a background thread, so that tests can create hidden windows,
+/// inject synthetic window events and check what ends up in the queue seen by the host.
+/// Winit allows only one event loop per process, start the harness once and share it.
+pub struct TestApplication {
+    application_handle: ApplicationHandle,
+}
+
+impl TestApplication {
+    pub fn start() -> anyhow::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("winit-test-harness".to_string())
+            .spawn(move || {
+                let mut application_builder = ApplicationBuilder::new();
+                application_builder.with_any_thread();
+                match application_builder.build() {
+                    Ok((application, application_handle)) => {
+                        let _ = sender.send(Ok(application_handle));
+                        application.run();
+                    }
+                    Err(error) => {
+                        let _ = sender.send(Err(error));
+                    }
+                }
+            })?;
+
+        let application_handle = receiver
+            .recv_timeout(TIMEOUT)
+            .map_err(|error| anyhow!("The event loop did not start: {}", error))??;
+        Ok(Self { application_handle })
+    }
+
+    pub fn application_handle(&self) -> &ApplicationHandle {
+        &self.application_handle
+    }
+
+    pub fn create_hidden_window(&self) -> anyhow::Result<WindowHandle> {
+        let (sender, receiver) = mpsc::channel();
+        self.application_handle.create_window(
+            WindowAttributes::default().with_visible(false),
+            move |window_handle, _| {
+                let _ = sender.send(window_handle);
+            },
+        );
+        receiver
+            .recv_timeout(TIMEOUT)
+            .map_err(|error| anyhow!("The window was not created: {}", error))
+    }
+
+    /// Deliver the event to the window as if the platform sent it
+    pub fn inject_event(&self, window_handle: &WindowHandle, event: WindowEvent) {
+        self.application_handle
+            .enqueue_action(ApplicationAction::InjectWindowEvent(
+                InjectWindowEventAction {
+                    window_id: window_handle.id(),
+                    event,
+                },
+            ));
+    }
+
+    /// Drop queued events until one of the given type arrives for the window
+    pub fn wait_for_event(
+        &self,
+        window_handle: &WindowHandle,
+        event_type: WinitEventType,
+    ) -> Option<WinitWindowEvent> {
+        let deadline = Instant::now() + TIMEOUT;
+        while Instant::now() < deadline {
+            match self.application_handle.pop_event() {
+                Some(event)
+                    if event.window_id() == Some(window_handle.id())
+                        && event.event_type() == event_type =>
+                {
+                    return Some(event);
+                }
+                Some(_) => {}
+                None => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WinitEventReceivedTextUtf32, WinitTextEncoding};
+    use winit::event::Ime;
+
+    #[test]
+    fn delivers_injected_events() {
+        let application = TestApplication::start().unwrap();
+        let window = application.create_hidden_window().unwrap();
+
+        application.inject_event(&window, WindowEvent::Focused(true));
+        assert!(application
+            .wait_for_event(&window, WinitEventType::WindowEventFocused)
+            .is_some());
+
+        application
+            .application_handle()
+            .update_settings(|settings| settings.text_encoding = WinitTextEncoding::Utf32);
+        application.inject_event(&window, WindowEvent::Ime(Ime::Commit("é".to_string())));
+        let event = application
+            .wait_for_event(&window, WinitEventType::Winit30WindowEventReceivedTextUtf32)
+            .unwrap();
+        assert_eq!(
+            event
+                .downcast_ref::<WinitEventReceivedTextUtf32>()
+                .map(|event| event.code_points().to_vec()),
+            Some(vec![0xE9])
+        );
+    }
+}
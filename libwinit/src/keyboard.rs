@@ -1,4 +1,5 @@
-use winit::keyboard::KeyLocation;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, KeyCode, KeyLocation, NamedKey, PhysicalKey};
 
 macro_rules! map_keys {(
     $(
@@ -7,6 +8,7 @@ macro_rules! map_keys {(
     ),+ $(,)?
 ) => (
     #[derive(Copy, Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
     #[repr(u32)]
     pub
     enum VirtualKeyCode {
@@ -734,6 +736,7 @@ map_keys! {
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum WinitKeyLocation {
     Standard,
@@ -752,3 +755,37 @@ impl From<KeyLocation> for WinitKeyLocation {
         }
     }
 }
+
+/// Winit does not report the state of the lock keys, instead the num lock state is derived
+/// from the keyboard events. Numpad digit keys produce digits only while num lock is on,
+/// which also corrects the initially unknown state, otherwise the state is toggled by
+/// presses of the num lock key.
+pub fn track_num_lock(event: &KeyEvent, is_synthetic: bool, num_lock: bool) -> bool {
+    if event.state != ElementState::Pressed || event.repeat {
+        return num_lock;
+    }
+
+    match (&event.physical_key, &event.logical_key) {
+        (PhysicalKey::Code(code), key) if is_numpad_digit(*code) => {
+            matches!(key, Key::Character(_))
+        }
+        (_, Key::Named(NamedKey::NumLock)) if !is_synthetic => !num_lock,
+        _ => num_lock,
+    }
+}
+
+fn is_numpad_digit(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Numpad0
+            | KeyCode::Numpad1
+            | KeyCode::Numpad2
+            | KeyCode::Numpad3
+            | KeyCode::Numpad4
+            | KeyCode::Numpad5
+            | KeyCode::Numpad6
+            | KeyCode::Numpad7
+            | KeyCode::Numpad8
+            | KeyCode::Numpad9
+    )
+}
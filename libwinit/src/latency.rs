@@ -0,0 +1,136 @@
+use crate::{CallbackRegistry, WinitWindowEvent};
+use std::os::raw::c_void;
+use std::time::{Duration, Instant};
+
+/// Diagnostic mode measuring how long input events wait in the queue, from the moment they
+/// are received from the OS until the host pops them. Latencies are expressed in the units of
+/// the event clock and are periodically reported to the host as percentiles.
+#[derive(Debug)]
+pub struct LatencyProbe {
+    interval: Duration,
+    callback: LatencyProbeCallback,
+    samples: Vec<u64>,
+    last_report: Instant,
+}
+
+impl LatencyProbe {
+    pub fn new(interval: Duration, callback: LatencyProbeCallback) -> Self {
+        Self {
+            interval,
+            callback,
+            samples: vec![],
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Record the latency of a popped event.
+    /// Returns a report to deliver to the host (outside of the lock) once the interval is over.
+    pub fn record(&mut self, event: &WinitWindowEvent, now: u64) -> Option<LatencyProbeReport> {
+        if event.event_type().is_input() {
+            self.samples.push(now.saturating_sub(event.timestamp()));
+        }
+
+        if self.last_report.elapsed() < self.interval {
+            return None;
+        }
+        self.last_report = Instant::now();
+
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let latency = WinitLatencyReport::from_samples(&mut self.samples);
+        self.samples.clear();
+
+        Some(LatencyProbeReport {
+            callback: self.callback.clone(),
+            latency,
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct WinitLatencyReport {
+    count: usize,
+    p50: u64,
+    p90: u64,
+    p99: u64,
+    max: u64,
+}
+
+impl WinitLatencyReport {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn from_samples(samples: &mut [u64]) -> Self {
+        samples.sort_unstable();
+        let percentile = |percent: usize| samples[(samples.len() - 1) * percent / 100];
+
+        Self {
+            count: samples.len(),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: percentile(100),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyProbeCallback {
+    thunk: *const c_void,
+    token: usize,
+    callback: unsafe extern "C" fn(*const c_void, WinitLatencyReport),
+}
+
+unsafe impl Send for LatencyProbeCallback {}
+unsafe impl Sync for LatencyProbeCallback {}
+
+impl LatencyProbeCallback {
+    pub fn new(
+        callback: unsafe extern "C" fn(*const c_void, WinitLatencyReport),
+        thunk: *const c_void,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LatencyProbeReport {
+    callback: LatencyProbeCallback,
+    latency: WinitLatencyReport,
+}
+
+impl LatencyProbeReport {
+    pub fn notify(self) {
+        if !CallbackRegistry::is_valid(self.callback.token) {
+            return;
+        }
+        unsafe {
+            (self.callback.callback)(self.callback.thunk, self.latency);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_percentiles() {
+        let mut samples: Vec<u64> = (1..=100).rev().collect();
+        let report = WinitLatencyReport::from_samples(&mut samples);
+
+        assert_eq!(report.count, 100);
+        assert_eq!(report.p50, 50);
+        assert_eq!(report.p90, 90);
+        assert_eq!(report.p99, 99);
+        assert_eq!(report.max, 100);
+    }
+}
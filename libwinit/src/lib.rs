@@ -6,25 +6,90 @@ extern crate anyhow;
 extern crate log;
 #[macro_use]
 extern crate value_box;
+
+/// Enter a tracing span until the end of the scope, does nothing without the `tracing` feature
+macro_rules! trace_span {
+    ($($arguments:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($($arguments)*).entered();
+    };
+}
+
 mod actions;
 mod application;
+mod callbacks;
+mod capabilities;
+mod clicks;
+mod clock;
+mod coordinates;
 mod cursor;
+mod device_ids;
+mod errors;
+mod event_batch;
+mod event_sinks;
 mod events;
+#[cfg(feature = "test-harness")]
+mod harness;
 mod keyboard;
+mod latency;
+mod liveness;
 mod monitor;
+#[cfg(windows_platform)]
+mod msg_hook;
+mod redraw_throttle;
+mod settings;
 mod signallers;
+mod subscriptions;
+mod support;
+mod surface_token;
+mod system_settings;
+mod thread;
+#[cfg(feature = "tracing")]
+mod trace_recording;
+mod watchdog;
 mod window;
 mod window_attributes;
+mod window_config;
+#[cfg(feature = "serde")]
+mod window_profile;
 
 pub use actions::*;
 pub use application::*;
+pub use callbacks::*;
+pub use capabilities::*;
+pub use clicks::*;
+pub use clock::*;
+pub use coordinates::*;
 pub use cursor::*;
+pub use device_ids::*;
+pub use errors::*;
+pub use event_batch::*;
+pub use event_sinks::*;
 pub use events::*;
+#[cfg(feature = "test-harness")]
+pub use harness::*;
 pub use keyboard::*;
+pub use latency::*;
+pub use liveness::*;
 pub use monitor::*;
+#[cfg(windows_platform)]
+pub use msg_hook::*;
+pub use redraw_throttle::*;
+pub use settings::*;
 pub use signallers::*;
+pub use subscriptions::*;
+pub use support::*;
+pub use surface_token::*;
+pub use system_settings::*;
+pub use thread::*;
+#[cfg(feature = "tracing")]
+pub use trace_recording::*;
+pub use watchdog::*;
 pub use window::*;
 pub use window_attributes::*;
+pub use window_config::*;
+#[cfg(feature = "serde")]
+pub use window_profile::*;
 
 pub use value_box_ffi::*;
 
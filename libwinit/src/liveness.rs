@@ -0,0 +1,71 @@
+use crate::CallbackRegistry;
+use std::os::raw::c_void;
+use std::time::{Duration, Instant};
+
+/// Tells the host that the UI thread is still alive while it is busy inside of a native modal
+/// loop (menu tracking, live resize or move), when the host does not get to pop events.
+/// It is ticked from every callback winit delivers to the application, including the ones
+/// dispatched from within the modal loops, and notifies the host at most once per interval.
+#[derive(Debug)]
+pub struct LivenessNotifier {
+    interval: Duration,
+    last_notification: Option<Instant>,
+    callback: LivenessCallback,
+}
+
+impl LivenessNotifier {
+    pub fn new(interval: Duration, callback: LivenessCallback) -> Self {
+        Self {
+            interval,
+            last_notification: None,
+            callback,
+        }
+    }
+
+    /// Returns the callback to notify if a notification is due.
+    /// Must be called from a UI thread
+    pub fn tick(&mut self) -> Option<LivenessCallback> {
+        let now = Instant::now();
+        let is_due = match self.last_notification {
+            None => true,
+            Some(last_notification) => now.duration_since(last_notification) >= self.interval,
+        };
+
+        is_due.then(|| {
+            self.last_notification = Some(now);
+            self.callback.clone()
+        })
+    }
+
+    /// When the next notification is due, so that an idle event loop wakes up for it
+    pub fn next_notification(&self) -> Option<Instant> {
+        self.last_notification
+            .map(|last_notification| last_notification + self.interval)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LivenessCallback {
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+    token: usize,
+}
+
+unsafe impl Send for LivenessCallback {}
+unsafe impl Sync for LivenessCallback {}
+
+impl LivenessCallback {
+    pub fn new(callback: unsafe extern "C" fn(*const c_void), thunk: *const c_void) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    pub fn notify(&self) {
+        if CallbackRegistry::is_valid(self.token) {
+            unsafe { (self.callback)(self.thunk) };
+        }
+    }
+}
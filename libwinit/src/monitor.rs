@@ -1,7 +1,94 @@
 use geometry_box::SizeBox;
 use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event_loop::ActiveEventLoop;
 use winit::monitor::MonitorHandle;
 
+/// The part of the desktop covered by a monitor or a window, in physical pixels
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ScreenArea {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+}
+
+impl ScreenArea {
+    /// None for monitors without a position, for example on Wayland
+    fn of(monitor_handle: &MonitorHandle) -> Option<Self> {
+        Some(Self {
+            position: monitor_handle.position()?,
+            size: monitor_handle.current_video_mode()?.size(),
+        })
+    }
+
+    pub fn all(event_loop: &dyn ActiveEventLoop) -> Vec<Self> {
+        event_loop
+            .available_monitors()
+            .filter_map(|monitor_handle| Self::of(&monitor_handle))
+            .collect()
+    }
+
+    fn right(&self) -> i32 {
+        self.position.x + self.size.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.position.y + self.size.height as i32
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.position.x < other.right()
+            && other.position.x < self.right()
+            && self.position.y < other.bottom()
+            && other.position.y < self.bottom()
+    }
+
+    /// Squared distance from the center of the other area to the closest point of this one
+    fn distance_to(&self, other: &Self) -> i64 {
+        let center_x = (other.position.x + other.right()) as i64 / 2;
+        let center_y = (other.position.y + other.bottom()) as i64 / 2;
+        let dx = center_x - center_x.clamp(self.position.x as i64, self.right() as i64);
+        let dy = center_y - center_y.clamp(self.position.y as i64, self.bottom() as i64);
+        dx * dx + dy * dy
+    }
+
+    /// Where to move the window so that it is fully visible on this monitor,
+    /// or at least its top left corner when it is larger than the monitor
+    fn fit(&self, window: &Self) -> PhysicalPosition<i32> {
+        let fit_axis = |position: i32, start: i32, end: i32, length: u32| {
+            position.min(end - length as i32).max(start)
+        };
+        PhysicalPosition::new(
+            fit_axis(
+                window.position.x,
+                self.position.x,
+                self.right(),
+                window.size.width,
+            ),
+            fit_axis(
+                window.position.y,
+                self.position.y,
+                self.bottom(),
+                window.size.height,
+            ),
+        )
+    }
+}
+
+/// A new position on the nearest monitor for a window that is entirely off-screen,
+/// None if the window is at least partially visible or there are no monitors
+pub fn offscreen_window_position(
+    window: &ScreenArea,
+    monitors: &[ScreenArea],
+) -> Option<PhysicalPosition<i32>> {
+    if monitors.iter().any(|monitor| monitor.intersects(window)) {
+        return None;
+    }
+    monitors
+        .iter()
+        .min_by_key(|monitor| monitor.distance_to(window))
+        .map(|monitor| monitor.fit(window))
+}
+
 #[no_mangle]
 pub extern "C" fn winit_monitor_get_hidpi_factor(
     monitor_handle: *mut ValueBox<MonitorHandle>,
@@ -33,3 +120,33 @@ pub extern "C" fn winit_monitor_get_size(
 pub extern "C" fn winit_monitor_drop(ptr: *mut ValueBox<MonitorHandle>) {
     ptr.release();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(x: i32, y: i32, width: u32, height: u32) -> ScreenArea {
+        ScreenArea {
+            position: PhysicalPosition::new(x, y),
+            size: PhysicalSize::new(width, height),
+        }
+    }
+
+    #[test]
+    fn moves_lost_windows_to_the_nearest_monitor() {
+        let monitors = [area(0, 0, 1920, 1080), area(-1280, 0, 1280, 1024)];
+
+        assert_eq!(
+            offscreen_window_position(&area(1800, 100, 800, 600), &monitors),
+            None
+        );
+        assert_eq!(
+            offscreen_window_position(&area(2200, 300, 800, 600), &monitors),
+            Some(PhysicalPosition::new(1120, 300))
+        );
+        assert_eq!(
+            offscreen_window_position(&area(-3000, -900, 800, 600), &monitors),
+            Some(PhysicalPosition::new(-1280, 0))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::CallbackRegistry;
+use std::os::raw::c_void;
+use std::sync::Arc;
+use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::UI::Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass};
+use windows_sys::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+/// Tells our subclass apart from the ones installed by winit or the host
+const MSG_HOOK_SUBCLASS_ID: usize = 0x5749_4e54;
+
+/// Mirrors the layout of the Win32 `MSG` struct passed to the message loop hook
+#[allow(dead_code)]
+#[repr(C)]
+pub(crate) struct Win32Msg {
+    hwnd: *mut c_void,
+    message: u32,
+    wparam: usize,
+    lparam: isize,
+    time: u32,
+    pt_x: i32,
+    pt_y: i32,
+}
+
+/// Sees the messages of the UI thread before winit handles them: the messages posted to the
+/// thread itself from the message loop, and the messages of the windows, posted or sent,
+/// from their window procedure.
+#[derive(Debug)]
+pub struct Win32MessageHook {
+    callback: unsafe extern "C" fn(*const c_void, *mut c_void, u32, usize, isize) -> bool,
+    thunk: *const c_void,
+    token: usize,
+}
+
+unsafe impl Send for Win32MessageHook {}
+unsafe impl Sync for Win32MessageHook {}
+
+impl Win32MessageHook {
+    pub fn new(
+        callback: unsafe extern "C" fn(*const c_void, *mut c_void, u32, usize, isize) -> bool,
+        thunk: *const c_void,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    /// Messages addressed to a window reach its window procedure once they are dispatched,
+    /// only the thread messages are passed to the callback from the message loop
+    pub(crate) fn on_thread_message(&self, msg: *const Win32Msg) -> bool {
+        if msg.is_null() {
+            return false;
+        }
+        let msg = unsafe { &*msg };
+        if !msg.hwnd.is_null() {
+            return false;
+        }
+        self.call(msg.hwnd, msg.message, msg.wparam, msg.lparam)
+    }
+
+    fn call(&self, hwnd: *mut c_void, message: u32, wparam: usize, lparam: isize) -> bool {
+        if !CallbackRegistry::is_valid(self.token) {
+            return false;
+        }
+        unsafe { (self.callback)(self.thunk, hwnd, message, wparam, lparam) }
+    }
+
+    /// Subclass the window procedure of a created window, the subclass removes itself once
+    /// the window is destroyed
+    pub(crate) fn hook_window(self: &Arc<Self>, window: &dyn Window) {
+        let hwnd = match window.window_handle().map(|handle| handle.as_raw()) {
+            Ok(RawWindowHandle::Win32(handle)) => handle.hwnd.get() as HWND,
+            _ => return,
+        };
+        let hook = Arc::into_raw(self.clone());
+        let is_hooked = unsafe {
+            SetWindowSubclass(
+                hwnd,
+                Some(msg_hook_subclass_proc),
+                MSG_HOOK_SUBCLASS_ID,
+                hook as usize,
+            )
+        } != 0;
+        if !is_hooked {
+            drop(unsafe { Arc::from_raw(hook) });
+            warn!("Failed to hook the window procedure of {:?}", hwnd);
+        }
+    }
+}
+
+unsafe extern "system" fn msg_hook_subclass_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    subclass_id: usize,
+    hook: usize,
+) -> LRESULT {
+    let hook = hook as *const Win32MessageHook;
+    if message == WM_NCDESTROY {
+        RemoveWindowSubclass(hwnd, Some(msg_hook_subclass_proc), subclass_id);
+        drop(Arc::from_raw(hook));
+        return DefSubclassProc(hwnd, message, wparam, lparam);
+    }
+    // a handled message is not passed on to winit
+    if (*hook).call(hwnd as *mut c_void, message, wparam, lparam) {
+        return 0;
+    }
+    DefSubclassProc(hwnd, message, wparam, lparam)
+}
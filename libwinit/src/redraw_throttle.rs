@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+/// Makes the event loop wait at least the minimum idle time between continuously redrawn
+/// frames. Winit handles pending redraw requests before it looks at the control flow, so the
+/// redraws requested by the host are held back and requested once the interval is over.
+#[derive(Debug)]
+pub struct RedrawThrottle {
+    minimum_idle_sleep: Duration,
+    last_release: Option<Instant>,
+}
+
+impl RedrawThrottle {
+    pub fn new(minimum_idle_sleep: Duration) -> Self {
+        Self {
+            minimum_idle_sleep,
+            last_release: None,
+        }
+    }
+
+    /// Disabled with a zero minimum idle sleep, redraws are then requested right away
+    pub fn is_enabled(&self) -> bool {
+        !self.minimum_idle_sleep.is_zero()
+    }
+
+    /// Returns None if the held back redraws can be requested now, recording the release,
+    /// otherwise when they can be
+    pub fn try_release(&mut self, now: Instant) -> Option<Instant> {
+        if let Some(last_release) = self.last_release {
+            let deadline = last_release + self.minimum_idle_sleep;
+            if now < deadline {
+                return Some(deadline);
+            }
+        }
+        self.last_release = Some(now);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn releases_redraws_once_per_interval() {
+        let mut throttle = RedrawThrottle::new(Duration::from_millis(10));
+        let start = Instant::now();
+
+        // the host requests a redraw every millisecond
+        let redraws = (0..100)
+            .map(|millis| start + Duration::from_millis(millis))
+            .filter(|now| throttle.try_release(*now).is_none())
+            .count();
+
+        assert_eq!(redraws, 10);
+        assert_eq!(
+            throttle.try_release(start + Duration::from_millis(95)),
+            Some(start + Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn releases_the_first_redraw_right_away() {
+        let mut throttle = RedrawThrottle::new(Duration::from_millis(10));
+        assert!(throttle.is_enabled());
+        assert_eq!(throttle.try_release(Instant::now()), None);
+        assert!(!RedrawThrottle::new(Duration::ZERO).is_enabled());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{WinitError, WinitErrorCode};
+use std::time::Duration;
+
+/// Application wide settings that affect how events are converted.
+/// Can be changed by the host at any time, changes apply to the following events.
+#[derive(Debug, Clone)]
+pub struct ApplicationSettings {
+    pub scroll: ScrollSettings,
+    pub clicks: ClickSettings,
+    pub synthetic_keys: WinitSyntheticKeyPolicy,
+    /// Also deliver `RedrawRequested` through the event queue, in addition to the
+    /// per-window redraw listeners. Disabled by default.
+    pub enqueue_redraw_requested: bool,
+    /// Deliver raw device button and key events, even when none of the windows is focused.
+    /// Disabled by default.
+    pub raw_device_input: bool,
+    /// Deliver the relative motion of pointer devices, for example for mouse look, which
+    /// would otherwise flood the queue with every movement. Disabled by default.
+    pub device_pointer_motion: bool,
+    /// Only events which type's bit is set are enqueued, see [`crate::WinitEventType::mask`].
+    /// All events are enqueued by default.
+    pub event_mask: u64,
+    /// Move the IME candidate window to the caret area last posted by the host, each time
+    /// before the event loop waits for new events. Disabled by default.
+    pub follow_ime_caret: bool,
+    /// How received text is delivered, as UTF-8 strings by default
+    pub text_encoding: WinitTextEncoding,
+    /// Which text of a key press is delivered as received text
+    pub text_source: WinitTextSource,
+    /// Whether mouse, touch and pen input is delivered as separate event families
+    pub pointer_events: WinitPointerEventMode,
+    /// Deliver files dropped together as one dropped files event rather than an event for each
+    /// file. Disabled by default.
+    pub batch_dropped_files: bool,
+    /// Move windows that are entirely off-screen after the monitors change back onto the
+    /// nearest monitor. Disabled by default.
+    pub reposition_offscreen_windows: bool,
+    /// Host callbacks on the UI thread that take longer are reported with a diagnostics event.
+    /// Callbacks are not measured by default.
+    pub callback_budget: Option<Duration>,
+}
+
+impl Default for ApplicationSettings {
+    fn default() -> Self {
+        Self {
+            scroll: Default::default(),
+            clicks: Default::default(),
+            synthetic_keys: Default::default(),
+            enqueue_redraw_requested: false,
+            raw_device_input: false,
+            device_pointer_motion: false,
+            event_mask: u64::MAX,
+            follow_ime_caret: false,
+            text_encoding: Default::default(),
+            text_source: Default::default(),
+            pointer_events: Default::default(),
+            batch_dropped_files: false,
+            reposition_offscreen_windows: false,
+            callback_budget: None,
+        }
+    }
+}
+
+/// Synthetic keyboard events are sent by some platforms (Windows, X11) for keys that are
+/// already pressed when a window gains focus, for example after Alt+Tab.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitSyntheticKeyPolicy {
+    /// Deliver synthetic key events and their text, with `is_synthetic` set.
+    Deliver,
+    /// Deliver synthetic key events with `is_synthetic` set, but do not produce text from them.
+    SuppressText,
+    /// Do not deliver synthetic key events at all.
+    Suppress,
+}
+
+impl Default for WinitSyntheticKeyPolicy {
+    fn default() -> Self {
+        Self::Deliver
+    }
+}
+
+/// The representation of the text in received text events
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitTextEncoding {
+    /// `Winit30WindowEventReceivedText` events with the text in a string box
+    Utf8,
+    /// `Winit30WindowEventReceivedTextUtf32` events with an array of code points
+    Utf32,
+}
+
+impl Default for WinitTextEncoding {
+    fn default() -> Self {
+        Self::Utf8
+    }
+}
+
+/// The text of a key press that produces received text events. The two differ when the
+/// modifiers alter the produced character, for example Alt on macOS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitTextSource {
+    /// The text with all modifiers applied, including the ones that winit treats as shortcuts
+    TextWithAllModifiers,
+    /// The text as produced by the keyboard layout
+    Text,
+}
+
+impl Default for WinitTextSource {
+    fn default() -> Self {
+        Self::TextWithAllModifiers
+    }
+}
+
+/// How mouse, touch and pen input is delivered to the host, passed as its `u8` value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitPointerEventMode {
+    /// Mouse input, cursor and touch events, each for their kind of device
+    Split,
+    /// `Winit30WindowEventPointer` events for all kinds of devices
+    Unified,
+}
+
+impl Default for WinitPointerEventMode {
+    fn default() -> Self {
+        Self::Split
+    }
+}
+
+/// A value out of the range of the enum would be undefined behaviour, it is rejected instead
+impl TryFrom<u8> for WinitPointerEventMode {
+    type Error = WinitError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Split),
+            1 => Ok(Self::Unified),
+            _ => Err(WinitError::new(
+                WinitErrorCode::InvalidArgument,
+                format!("Unknown pointer event mode {}", value),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ScrollSettings {
+    pub invert_x: bool,
+    pub invert_y: bool,
+    /// When set, line deltas are converted to pixel deltas with this many pixels per line
+    pub pixels_per_line: Option<f64>,
+}
+
+impl ScrollSettings {
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let x = if self.invert_x { -x } else { x };
+        let y = if self.invert_y { -y } else { y };
+        (x, y)
+    }
+}
+
+impl Default for ScrollSettings {
+    fn default() -> Self {
+        // horizontal scrolling is inverted to match the host conventions
+        Self {
+            invert_x: true,
+            invert_y: false,
+            pixels_per_line: None,
+        }
+    }
+}
+
+/// Consecutive presses of a mouse button make a multi-click when each one follows the previous
+/// within the interval and the distance. Disabled by default.
+#[derive(Debug, Copy, Clone)]
+pub struct ClickSettings {
+    pub is_enabled: bool,
+    pub interval: Duration,
+    /// In physical pixels
+    pub distance: f64,
+}
+
+impl Default for ClickSettings {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            interval: Duration::from_millis(500),
+            distance: 4.0,
+        }
+    }
+}
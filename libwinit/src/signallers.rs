@@ -1,3 +1,4 @@
+use crate::CallbackRegistry;
 use std::os::raw::c_void;
 use value_box::{ValueBox, ValueBoxPointer};
 
@@ -5,14 +6,23 @@ use value_box::{ValueBox, ValueBoxPointer};
 pub struct WakeUpSignaller {
     callback: unsafe extern "C" fn(*const c_void),
     thunk: *const c_void,
+    token: usize,
 }
 
 impl WakeUpSignaller {
     pub fn new(callback: unsafe extern "C" fn(*const c_void), thunk: *const c_void) -> Self {
-        Self { callback, thunk }
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
     }
 
     pub fn signal(&self) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("wake_up_signaller");
         let callback = self.callback;
         unsafe { callback(self.thunk) };
     }
@@ -23,8 +33,12 @@ pub struct SemaphoreSignaller {
     semaphore_callback: unsafe extern "C" fn(usize, *const c_void),
     semaphore_index: usize,
     semaphore_thunk: *const c_void,
+    token: usize,
 }
 
+unsafe impl Send for SemaphoreSignaller {}
+unsafe impl Sync for SemaphoreSignaller {}
+
 impl SemaphoreSignaller {
     pub fn new(
         semaphore_callback: unsafe extern "C" fn(usize, *const c_void),
@@ -35,10 +49,15 @@ impl SemaphoreSignaller {
             semaphore_callback,
             semaphore_index,
             semaphore_thunk,
+            token: CallbackRegistry::token_of(semaphore_thunk),
         }
     }
 
     pub fn signal(&self) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("semaphore_signaller");
         let callback = self.semaphore_callback;
         unsafe { callback(self.semaphore_index, self.semaphore_thunk) };
     }
@@ -0,0 +1,58 @@
+use crate::SemaphoreSignaller;
+use winit::window::WindowId;
+
+/// Per-window semaphores registered by the host, signalled when events of the subscribed
+/// types are enqueued for that window. Lets per-window host processes wake up independently.
+#[derive(Debug, Default)]
+pub struct EventSubscriptions {
+    next_id: usize,
+    subscriptions: Vec<EventSubscription>,
+}
+
+#[derive(Debug)]
+struct EventSubscription {
+    id: usize,
+    window_id: WindowId,
+    event_mask: u64,
+    semaphore: SemaphoreSignaller,
+}
+
+impl EventSubscriptions {
+    /// Subscribe to events which type's bit is set in the mask, see [`crate::WinitEventType::mask`].
+    /// Returns the id of the subscription.
+    pub fn subscribe(
+        &mut self,
+        window_id: WindowId,
+        event_mask: u64,
+        semaphore: SemaphoreSignaller,
+    ) -> usize {
+        self.next_id += 1;
+        self.subscriptions.push(EventSubscription {
+            id: self.next_id,
+            window_id,
+            event_mask,
+            semaphore,
+        });
+        self.next_id
+    }
+
+    pub fn unsubscribe(&mut self, id: usize) {
+        self.subscriptions
+            .retain(|subscription| subscription.id != id);
+    }
+
+    pub fn unsubscribe_window(&mut self, window_id: WindowId) {
+        self.subscriptions
+            .retain(|subscription| subscription.window_id != window_id);
+    }
+
+    /// Signal the semaphores subscribed to any of the event types in the mask
+    pub fn notify(&self, window_id: WindowId, event_mask: u64) {
+        self.subscriptions
+            .iter()
+            .filter(|subscription| {
+                subscription.window_id == window_id && subscription.event_mask & event_mask != 0
+            })
+            .for_each(|subscription| subscription.semaphore.signal());
+    }
+}
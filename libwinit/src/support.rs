@@ -0,0 +1,428 @@
+use string_box::StringBox;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+
+/// How well a function of the library works on the current platform
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitSupportLevel {
+    /// The function does what it says
+    Supported,
+    /// The platform has no native equivalent and the library approximates the behavior
+    Emulated,
+    /// The function is not available or has no effect on this platform
+    Unsupported,
+}
+
+impl Default for WinitSupportLevel {
+    fn default() -> Self {
+        Self::Unsupported
+    }
+}
+
+impl WinitSupportLevel {
+    fn when(is_supported: bool) -> Self {
+        if is_supported {
+            Self::Supported
+        } else {
+            Self::Unsupported
+        }
+    }
+
+    /// For functions that fall back to a default value where the platform has no such setting
+    fn emulated_unless(is_supported: bool) -> Self {
+        if is_supported {
+            Self::Supported
+        } else {
+            Self::Emulated
+        }
+    }
+}
+
+/// Names of all functions exported by the library, kept in sync with the sources by a test
+const EXPORTED_FUNCTIONS: &[&str] = &[
+    "winit_action_acknowledgement_new_callback",
+    "winit_action_acknowledgement_new_semaphore",
+    "winit_action_acknowledgement_release",
+    "winit_application_builder_add_wakeup_signaller",
+    "winit_application_builder_build",
+    "winit_application_builder_new",
+    "winit_application_builder_release",
+    "winit_application_builder_set_lazy_initialization",
+    "winit_application_builder_set_minimum_idle_sleep",
+    "winit_application_builder_set_semaphore_signaller",
+    "winit_application_builder_set_thread_name",
+    "winit_application_builder_set_thread_priority",
+    "winit_application_builder_with_android_app",
+    "winit_application_builder_with_msg_hook",
+    "winit_application_call_function",
+    "winit_application_call_function_with_priority",
+    "winit_application_handle_add_event_sink",
+    "winit_application_handle_create_focused_window",
+    "winit_application_handle_create_window",
+    "winit_application_handle_create_window_with_listeners",
+    "winit_application_handle_create_window_with_state",
+    "winit_application_handle_disable_latency_probe",
+    "winit_application_handle_enable_latency_probe",
+    "winit_application_handle_get_capabilities",
+    "winit_application_handle_get_modifiers",
+    "winit_application_handle_get_queue_latency",
+    "winit_application_handle_get_wayland_display",
+    "winit_application_handle_get_xlib_display",
+    "winit_application_handle_memory_usage",
+    "winit_application_handle_peek_event",
+    "winit_application_handle_pop_event",
+    "winit_application_handle_pop_event_with_timestamp",
+    "winit_application_handle_pop_events",
+    "winit_application_handle_push_user_event",
+    "winit_application_handle_raw_display_handle",
+    "winit_application_handle_release",
+    "winit_application_handle_release_get_type",
+    "winit_application_handle_remove_event_sink",
+    "winit_application_handle_remove_queue_watchdog",
+    "winit_application_handle_set_batch_dropped_files",
+    "winit_application_handle_set_callback_budget",
+    "winit_application_handle_set_click_counting",
+    "winit_application_handle_set_coalesce_cursor_moves",
+    "winit_application_handle_set_device_pointer_motion",
+    "winit_application_handle_set_enqueue_redraw_requested",
+    "winit_application_handle_set_event_callback",
+    "winit_application_handle_set_event_clock",
+    "winit_application_handle_set_event_mask",
+    "winit_application_handle_set_follow_ime_caret",
+    "winit_application_handle_set_liveness_callback",
+    "winit_application_handle_set_pointer_event_mode",
+    "winit_application_handle_set_queue_watchdog",
+    "winit_application_handle_set_raw_device_input",
+    "winit_application_handle_set_reposition_offscreen_windows",
+    "winit_application_handle_set_scroll_inversion",
+    "winit_application_handle_set_scroll_pixels_per_line",
+    "winit_application_handle_set_synthetic_key_policy",
+    "winit_application_handle_set_text_encoding",
+    "winit_application_handle_set_text_source",
+    "winit_application_handle_subscribe",
+    "winit_application_handle_swap_events",
+    "winit_application_handle_trim",
+    "winit_application_handle_unsubscribe",
+    "winit_application_release",
+    "winit_application_run",
+    "winit_application_wake",
+    "winit_application_waker_function",
+    "winit_callback_invalidate",
+    "winit_callback_register",
+    "winit_event_batch_get_event",
+    "winit_event_batch_get_event_type",
+    "winit_event_batch_get_len",
+    "winit_event_batch_get_queue_latency",
+    "winit_event_batch_get_timestamp",
+    "winit_event_batch_get_window_id",
+    "winit_event_batch_release",
+    "winit_event_sink_get_id",
+    "winit_event_sink_pop_event",
+    "winit_event_sink_release",
+    "winit_extension_event_ids_first",
+    "winit_extension_event_ids_last",
+    "winit_init_logger",
+    "winit_last_error_clear",
+    "winit_last_error_get_code",
+    "winit_last_error_get_message",
+    "winit_monitor_convert_point",
+    "winit_monitor_convert_rect",
+    "winit_monitor_convert_size",
+    "winit_monitor_drop",
+    "winit_monitor_get_hidpi_factor",
+    "winit_monitor_get_size",
+    "winit_semaphore_signaller_new",
+    "winit_semaphore_signaller_release",
+    "winit_string_box_list_at",
+    "winit_string_box_list_len",
+    "winit_supports",
+    "winit_surface_token_consume",
+    "winit_surface_token_release",
+    "winit_system_double_click_interval",
+    "winit_system_drag_threshold",
+    "winit_system_wheel_scroll_lines",
+    "winit_test",
+    "winit_trace_recording_start",
+    "winit_trace_recording_stop",
+    "winit_wakeup_signaller_new",
+    "winit_wakeup_signaller_release",
+    "winit_window_attributes_from_json",
+    "winit_window_attributes_new",
+    "winit_window_attributes_release",
+    "winit_window_attributes_with_always_on_top",
+    "winit_window_attributes_with_centered",
+    "winit_window_attributes_with_decorations",
+    "winit_window_attributes_with_dimensions",
+    "winit_window_attributes_with_full_size",
+    "winit_window_attributes_with_maximized",
+    "winit_window_attributes_with_resizable",
+    "winit_window_attributes_with_title",
+    "winit_window_attributes_with_transparency",
+    "winit_window_attributes_with_visibility",
+    "winit_window_event_as_action_failed",
+    "winit_window_event_as_activation_token_done",
+    "winit_window_event_as_callback_over_budget",
+    "winit_window_event_as_cursor_entered",
+    "winit_window_event_as_cursor_left",
+    "winit_window_event_as_cursor_moved",
+    "winit_window_event_as_dead_key",
+    "winit_window_event_as_device_button",
+    "winit_window_event_as_device_key",
+    "winit_window_event_as_device_mouse_motion",
+    "winit_window_event_as_double_tap_gesture",
+    "winit_window_event_as_dropped_file",
+    "winit_window_event_as_dropped_files",
+    "winit_window_event_as_extension",
+    "winit_window_event_as_focused",
+    "winit_window_event_as_hovered_file",
+    "winit_window_event_as_ime_preedit",
+    "winit_window_event_as_keyboard_input",
+    "winit_window_event_as_modifiers_changed",
+    "winit_window_event_as_monitor_changed",
+    "winit_window_event_as_mouse_input",
+    "winit_window_event_as_mouse_wheel",
+    "winit_window_event_as_moved",
+    "winit_window_event_as_pan_gesture",
+    "winit_window_event_as_pinch_gesture",
+    "winit_window_event_as_pointer",
+    "winit_window_event_as_received_text",
+    "winit_window_event_as_received_text_utf32",
+    "winit_window_event_as_repositioned",
+    "winit_window_event_as_resized",
+    "winit_window_event_as_scale_factor_changed",
+    "winit_window_event_as_state_changed",
+    "winit_window_event_as_theme_changed",
+    "winit_window_event_as_touch",
+    "winit_window_event_as_user_event",
+    "winit_window_event_as_window_recreated",
+    "winit_window_event_get_event_type",
+    "winit_window_event_get_queue_latency",
+    "winit_window_event_get_timestamp",
+    "winit_window_event_get_window_id",
+    "winit_window_event_release",
+    "winit_window_event_to_debug_string",
+    "winit_window_event_to_string",
+    "winit_window_handle_add_close_listener",
+    "winit_window_handle_add_focus_listener",
+    "winit_window_handle_add_redraw_listener",
+    "winit_window_handle_add_resize_listener",
+    "winit_window_handle_add_scale_factor_listener",
+    "winit_window_handle_apply_config",
+    "winit_window_handle_apply_config_acknowledged",
+    "winit_window_handle_close",
+    "winit_window_handle_convert_point",
+    "winit_window_handle_convert_rect",
+    "winit_window_handle_convert_size",
+    "winit_window_handle_current_monitor",
+    "winit_window_handle_exit_fullscreen",
+    "winit_window_handle_export_surface_token",
+    "winit_window_handle_focus_window",
+    "winit_window_handle_get_android_native_window",
+    "winit_window_handle_get_hwnd",
+    "winit_window_handle_get_id",
+    "winit_window_handle_get_ns_view",
+    "winit_window_handle_get_position",
+    "winit_window_handle_get_scale_factor",
+    "winit_window_handle_get_surface_size",
+    "winit_window_handle_get_title",
+    "winit_window_handle_get_wayland_display",
+    "winit_window_handle_get_wayland_surface",
+    "winit_window_handle_get_xlib_display",
+    "winit_window_handle_get_xlib_window",
+    "winit_window_handle_is_fullscreen",
+    "winit_window_handle_is_key_pressed",
+    "winit_window_handle_is_resizable",
+    "winit_window_handle_post_ime_caret_area",
+    "winit_window_handle_raw_display_handle",
+    "winit_window_handle_raw_window_handle",
+    "winit_window_handle_recreate",
+    "winit_window_handle_recreate_acknowledged",
+    "winit_window_handle_release",
+    "winit_window_handle_remove_listener",
+    "winit_window_handle_request_activation_token",
+    "winit_window_handle_request_focus",
+    "winit_window_handle_request_redraw",
+    "winit_window_handle_request_surface_size",
+    "winit_window_handle_request_surface_size_acknowledged",
+    "winit_window_handle_set_accepted_drop_types",
+    "winit_window_handle_set_cursor_icon",
+    "winit_window_handle_set_document_state",
+    "winit_window_handle_set_fullscreen_borderless",
+    "winit_window_handle_set_max_surface_size",
+    "winit_window_handle_set_min_surface_size",
+    "winit_window_handle_set_outer_position",
+    "winit_window_handle_set_resizable",
+    "winit_window_handle_set_title",
+    "winit_window_handle_set_window_icon",
+    "winit_window_surface_get_display_handle",
+    "winit_window_surface_get_height",
+    "winit_window_surface_get_scale_factor",
+    "winit_window_surface_get_width",
+    "winit_window_surface_get_window_handle",
+    "winit_window_surface_release",
+];
+
+/// The support level of an exported function given by its name. Only the platform dependent
+/// functions are listed, every other exported function is supported everywhere and unknown
+/// names are unsupported.
+/// The level is decided at compile time, so the X11 and Wayland specific functions are
+/// reported as supported on both kinds of Linux desktops.
+pub fn support_level(function_name: &str) -> WinitSupportLevel {
+    match function_name {
+        "winit_window_handle_get_android_native_window"
+        | "winit_application_builder_with_android_app" => {
+            WinitSupportLevel::when(cfg!(android_platform))
+        }
+        "winit_window_handle_get_ns_view" | "winit_window_attributes_with_full_size" => {
+            WinitSupportLevel::when(cfg!(macos_platform))
+        }
+        "winit_window_handle_get_hwnd" | "winit_application_builder_with_msg_hook" => {
+            WinitSupportLevel::when(cfg!(windows_platform))
+        }
+        "winit_window_handle_get_xlib_display"
+        | "winit_window_handle_get_xlib_window"
+        | "winit_application_handle_get_xlib_display" => {
+            WinitSupportLevel::when(cfg!(x11_platform))
+        }
+        "winit_window_handle_get_wayland_surface"
+        | "winit_window_handle_get_wayland_display"
+        | "winit_application_handle_get_wayland_display" => {
+            WinitSupportLevel::when(cfg!(wayland_platform))
+        }
+        "winit_window_handle_request_activation_token" => {
+            WinitSupportLevel::when(cfg!(any(x11_platform, wayland_platform)))
+        }
+        "winit_window_handle_set_window_icon" => {
+            WinitSupportLevel::when(cfg!(any(windows_platform, x11_platform)))
+        }
+        "winit_application_builder_set_thread_name"
+        | "winit_application_builder_set_thread_priority" => WinitSupportLevel::when(cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            apple,
+            windows_platform
+        ))),
+        "winit_system_double_click_interval" => {
+            WinitSupportLevel::emulated_unless(cfg!(any(windows_platform, macos_platform)))
+        }
+        "winit_system_drag_threshold" | "winit_system_wheel_scroll_lines" => {
+            WinitSupportLevel::emulated_unless(cfg!(windows_platform))
+        }
+        // tracked from the keyboard events rather than queried from the platform
+        "winit_window_handle_is_key_pressed" => WinitSupportLevel::Emulated,
+        // the native window is closed and a new one is created with the same attributes
+        "winit_window_handle_recreate" => WinitSupportLevel::Emulated,
+        // the title can not be read back, the last known title is returned instead
+        "winit_window_handle_get_title" => {
+            WinitSupportLevel::emulated_unless(!cfg!(wayland_platform))
+        }
+        "winit_window_handle_set_min_surface_size" | "winit_window_handle_set_max_surface_size" => {
+            WinitSupportLevel::when(!cfg!(any(ios_platform, android_platform, wasm_platform)))
+        }
+        _ if EXPORTED_FUNCTIONS.contains(&function_name) => WinitSupportLevel::Supported,
+        _ => WinitSupportLevel::Unsupported,
+    }
+}
+
+/// Query the support level of an exported function by its name, so that the host can
+/// disable features that do not work on the current platform.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_supports(function_name: *mut ValueBox<StringBox>) -> WinitSupportLevel {
+    function_name
+        .with_ref_ok(|function_name| support_level(function_name.as_str()))
+        .or_log(WinitSupportLevel::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_platform_dependent_functions() {
+        assert_eq!(
+            support_level("winit_window_handle_get_hwnd"),
+            WinitSupportLevel::when(cfg!(windows_platform))
+        );
+        assert_eq!(
+            support_level("winit_window_handle_recreate"),
+            WinitSupportLevel::Emulated
+        );
+        assert_eq!(
+            support_level("winit_window_handle_set_title"),
+            WinitSupportLevel::Supported
+        );
+        assert_eq!(
+            support_level("winit_window_handle_set_tittle"),
+            WinitSupportLevel::Unsupported
+        );
+    }
+
+    #[test]
+    fn lists_every_exported_function() {
+        let sources = [
+            include_str!("actions.rs"),
+            include_str!("application.rs"),
+            include_str!("callbacks.rs"),
+            include_str!("capabilities.rs"),
+            include_str!("clicks.rs"),
+            include_str!("clock.rs"),
+            include_str!("coordinates.rs"),
+            include_str!("cursor.rs"),
+            include_str!("device_ids.rs"),
+            include_str!("errors.rs"),
+            include_str!("event_batch.rs"),
+            include_str!("event_sinks.rs"),
+            include_str!("events.rs"),
+            include_str!("harness.rs"),
+            include_str!("keyboard.rs"),
+            include_str!("latency.rs"),
+            include_str!("lib.rs"),
+            include_str!("liveness.rs"),
+            include_str!("monitor.rs"),
+            include_str!("settings.rs"),
+            include_str!("signallers.rs"),
+            include_str!("subscriptions.rs"),
+            include_str!("support.rs"),
+            include_str!("surface_token.rs"),
+            include_str!("system_settings.rs"),
+            include_str!("thread.rs"),
+            include_str!("trace_recording.rs"),
+            include_str!("watchdog.rs"),
+            include_str!("window.rs"),
+            include_str!("window_attributes.rs"),
+            include_str!("window_config.rs"),
+            include_str!("window_profile.rs"),
+        ];
+        let mut exported_functions = vec![];
+        for source in sources {
+            let mut lines = source.lines().map(str::trim);
+            while let Some(line) = lines.next() {
+                if line == "#[no_mangle]" {
+                    let function = lines.find(|line| line.contains("fn ")).unwrap_or_default();
+                    exported_functions.extend(function_name(function.split("fn ").nth(1)));
+                }
+                if let Some(rest) = line.strip_prefix("event_downcast!(") {
+                    let rest = if rest.is_empty() {
+                        lines.next().unwrap_or_default()
+                    } else {
+                        rest
+                    };
+                    exported_functions.extend(function_name(Some(rest)));
+                }
+            }
+        }
+        exported_functions.sort();
+
+        let mut listed_functions = EXPORTED_FUNCTIONS.to_vec();
+        listed_functions.sort();
+        assert_eq!(listed_functions, exported_functions);
+    }
+
+    fn function_name(declaration: Option<&str>) -> Option<&str> {
+        declaration
+            .and_then(|declaration| declaration.split(['(', ',']).next())
+            .map(str::trim)
+            .filter(|name| name.starts_with("winit_"))
+    }
+}
@@ -0,0 +1,122 @@
+use crate::WindowHandle;
+use parking_lot::Mutex;
+use raw_window_handle_extensions::{VeryRawDisplayHandle, VeryRawWindowHandle};
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+
+/// Everything a render thread needs to create a rendering surface for a window
+#[derive(Debug)]
+pub struct WindowSurface {
+    window_handle: RawWindowHandle,
+    display_handle: RawDisplayHandle,
+    scale_factor: f64,
+    width: u32,
+    height: u32,
+}
+
+unsafe impl Send for WindowSurface {}
+unsafe impl Sync for WindowSurface {}
+
+/// Hands the raw handles of a window over to a render thread. The token is exported on the
+/// UI thread and can be consumed exactly once, from any thread.
+#[derive(Debug)]
+pub struct WindowSurfaceToken(Mutex<Option<WindowSurface>>);
+
+impl WindowSurfaceToken {
+    /// Must be called from a UI thread
+    pub fn export(window: &WindowHandle) -> anyhow::Result<Self> {
+        let window_handle = window
+            .raw_window_handle()
+            .map_err(|error| anyhow!("Failed to get the raw window handle: {}", error))?;
+        let display_handle = window
+            .raw_display_handle()
+            .map_err(|error| anyhow!("Failed to get the raw display handle: {}", error))?;
+        let surface_size = window.surface_size();
+
+        Ok(Self(Mutex::new(Some(WindowSurface {
+            window_handle,
+            display_handle,
+            scale_factor: window.scale_factor(),
+            width: surface_size.width,
+            height: surface_size.height,
+        }))))
+    }
+
+    /// Returns None if the token was already consumed
+    pub fn consume(&self) -> Option<WindowSurface> {
+        self.0.lock().take()
+    }
+}
+
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_export_surface_token(
+    window: *mut ValueBox<WindowHandle>,
+) -> *mut ValueBox<WindowSurfaceToken> {
+    window
+        .with_ref(|window| WindowSurfaceToken::export(window).map_err(|error| error.into()))
+        .map(|token| value_box!(token).into_raw())
+        .or_log(std::ptr::null_mut())
+}
+
+/// Returns null if the token was already consumed. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_surface_token_consume(
+    token: *mut ValueBox<WindowSurfaceToken>,
+) -> *mut ValueBox<WindowSurface> {
+    token
+        .with_ref_ok(|token| {
+            token
+                .consume()
+                .map(|surface| value_box!(surface).into_raw())
+                .unwrap_or_else(|| std::ptr::null_mut())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn winit_surface_token_release(token: *mut ValueBox<WindowSurfaceToken>) {
+    token.release();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_get_window_handle(
+    surface: *mut ValueBox<WindowSurface>,
+) -> *mut VeryRawWindowHandle {
+    surface
+        .with_ref_ok(|surface| VeryRawWindowHandle::from(surface.window_handle).into())
+        .or_log(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_get_display_handle(
+    surface: *mut ValueBox<WindowSurface>,
+) -> *mut VeryRawDisplayHandle {
+    surface
+        .with_ref_ok(|surface| VeryRawDisplayHandle::from(surface.display_handle).into())
+        .or_log(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_get_scale_factor(
+    surface: *mut ValueBox<WindowSurface>,
+) -> f64 {
+    surface
+        .with_ref_ok(|surface| surface.scale_factor)
+        .or_log(1.0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_get_width(surface: *mut ValueBox<WindowSurface>) -> u32 {
+    surface.with_ref_ok(|surface| surface.width).or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_get_height(surface: *mut ValueBox<WindowSurface>) -> u32 {
+    surface.with_ref_ok(|surface| surface.height).or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_surface_release(surface: *mut ValueBox<WindowSurface>) {
+    surface.release();
+}
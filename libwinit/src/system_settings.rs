@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+/// Used where the platform has no such setting, they match the defaults of Windows
+const DEFAULT_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_DRAG_THRESHOLD: u32 = 4;
+const DEFAULT_WHEEL_SCROLL_LINES: u32 = 3;
+
+/// The longest time between the clicks of a double click, as chosen by the user
+#[cfg(windows_platform)]
+pub fn double_click_interval() -> Duration {
+    Duration::from_millis(unsafe { win32::GetDoubleClickTime() } as u64)
+}
+
+#[cfg(macos_platform)]
+pub fn double_click_interval() -> Duration {
+    use cocoa::appkit::NSEvent;
+    use cocoa::base::nil;
+
+    let interval = unsafe { NSEvent::doubleClickInterval(nil) };
+    Duration::try_from_secs_f64(interval).unwrap_or(DEFAULT_DOUBLE_CLICK_INTERVAL)
+}
+
+#[cfg(not(any(windows_platform, macos_platform)))]
+pub fn double_click_interval() -> Duration {
+    DEFAULT_DOUBLE_CLICK_INTERVAL
+}
+
+/// How far in physical pixels the pointer moves with a pressed button before a drag starts
+#[cfg(windows_platform)]
+pub fn drag_threshold() -> u32 {
+    let (width, height) = unsafe {
+        (
+            win32::GetSystemMetrics(win32::SM_CXDRAG),
+            win32::GetSystemMetrics(win32::SM_CYDRAG),
+        )
+    };
+    match width.max(height) {
+        threshold if threshold > 0 => threshold as u32,
+        _ => DEFAULT_DRAG_THRESHOLD,
+    }
+}
+
+#[cfg(not(windows_platform))]
+pub fn drag_threshold() -> u32 {
+    DEFAULT_DRAG_THRESHOLD
+}
+
+/// How many lines a notch of the mouse wheel scrolls, `u32::MAX` to scroll a page at once
+#[cfg(windows_platform)]
+pub fn wheel_scroll_lines() -> u32 {
+    let mut lines: u32 = 0;
+    let result = unsafe {
+        win32::SystemParametersInfoW(
+            win32::SPI_GETWHEELSCROLLLINES,
+            0,
+            &mut lines as *mut u32 as *mut std::os::raw::c_void,
+            0,
+        )
+    };
+    if result == 0 {
+        warn!(
+            "Failed to read the wheel scroll lines: {}",
+            std::io::Error::last_os_error()
+        );
+        return DEFAULT_WHEEL_SCROLL_LINES;
+    }
+    lines
+}
+
+#[cfg(not(windows_platform))]
+pub fn wheel_scroll_lines() -> u32 {
+    DEFAULT_WHEEL_SCROLL_LINES
+}
+
+/// In milliseconds. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_system_double_click_interval() -> u64 {
+    double_click_interval().as_millis() as u64
+}
+
+/// In physical pixels. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_system_drag_threshold() -> u32 {
+    drag_threshold()
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_system_wheel_scroll_lines() -> u32 {
+    wheel_scroll_lines()
+}
+
+#[cfg(windows_platform)]
+mod win32 {
+    use std::os::raw::c_void;
+
+    pub const SM_CXDRAG: i32 = 68;
+    pub const SM_CYDRAG: i32 = 69;
+    pub const SPI_GETWHEELSCROLLLINES: u32 = 0x0068;
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn GetDoubleClickTime() -> u32;
+        pub fn GetSystemMetrics(index: i32) -> i32;
+        pub fn SystemParametersInfoW(
+            action: u32,
+            parameter: u32,
+            value: *mut c_void,
+            win_ini: u32,
+        ) -> i32;
+    }
+}
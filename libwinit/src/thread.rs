@@ -0,0 +1,134 @@
+/// Scheduling priority of the thread that runs the event loop
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitThreadPriority {
+    /// Keep the priority the thread was started with
+    Normal,
+    /// Favor the event loop over other threads of the host, for example when the host
+    /// saturates all cores. Maps to the user-interactive QoS class on macOS, to the highest
+    /// thread priority on Windows and to a lower nice value on Linux (requires privileges).
+    High,
+}
+
+impl Default for WinitThreadPriority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// Applied to the thread that runs the application, right before the event loop starts
+#[derive(Debug, Clone, Default)]
+pub struct EventLoopThreadSettings {
+    pub name: Option<String>,
+    pub priority: WinitThreadPriority,
+}
+
+impl EventLoopThreadSettings {
+    pub fn apply(&self) {
+        if let Some(name) = &self.name {
+            set_current_thread_name(name);
+        }
+        if self.priority == WinitThreadPriority::High {
+            raise_current_thread_priority();
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn set_current_thread_name(name: &str) {
+    // the name is limited to 16 bytes including the terminating zero
+    let mut length = name.len().min(15);
+    while !name.is_char_boundary(length) {
+        length -= 1;
+    }
+    match std::ffi::CString::new(&name[..length]) {
+        Ok(name) => {
+            let result = unsafe { libc::pthread_setname_np(libc::pthread_self(), name.as_ptr()) };
+            if result != 0 {
+                warn!("Failed to set the event loop thread name: {}", result);
+            }
+        }
+        Err(error) => warn!("Invalid event loop thread name: {}", error),
+    }
+}
+
+#[cfg(apple)]
+fn set_current_thread_name(name: &str) {
+    match std::ffi::CString::new(name) {
+        Ok(name) => {
+            let result = unsafe { libc::pthread_setname_np(name.as_ptr()) };
+            if result != 0 {
+                warn!("Failed to set the event loop thread name: {}", result);
+            }
+        }
+        Err(error) => warn!("Invalid event loop thread name: {}", error),
+    }
+}
+
+#[cfg(windows_platform)]
+fn set_current_thread_name(name: &str) {
+    let name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    let result = unsafe { win32::SetThreadDescription(win32::GetCurrentThread(), name.as_ptr()) };
+    if result < 0 {
+        warn!("Failed to set the event loop thread name: {:#x}", result);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", apple, windows_platform)))]
+fn set_current_thread_name(_name: &str) {
+    warn!("Naming the event loop thread is not supported on this platform");
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn raise_current_thread_priority() {
+    let result =
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, -10) };
+    if result != 0 {
+        warn!(
+            "Failed to raise the event loop thread priority: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(apple)]
+fn raise_current_thread_priority() {
+    let result = unsafe {
+        libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE, 0)
+    };
+    if result != 0 {
+        warn!("Failed to raise the event loop thread priority: {}", result);
+    }
+}
+
+#[cfg(windows_platform)]
+fn raise_current_thread_priority() {
+    let result = unsafe {
+        win32::SetThreadPriority(win32::GetCurrentThread(), win32::THREAD_PRIORITY_HIGHEST)
+    };
+    if result == 0 {
+        warn!(
+            "Failed to raise the event loop thread priority: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", apple, windows_platform)))]
+fn raise_current_thread_priority() {
+    warn!("Raising the event loop thread priority is not supported on this platform");
+}
+
+#[cfg(windows_platform)]
+mod win32 {
+    use std::os::raw::c_void;
+
+    pub const THREAD_PRIORITY_HIGHEST: i32 = 2;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetCurrentThread() -> *mut c_void;
+        pub fn SetThreadPriority(thread: *mut c_void, priority: i32) -> i32;
+        pub fn SetThreadDescription(thread: *mut c_void, description: *const u16) -> i32;
+    }
+}
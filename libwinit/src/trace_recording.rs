@@ -0,0 +1,72 @@
+use parking_lot::Mutex;
+use std::sync::OnceLock;
+use string_box::StringBox;
+use tracing_chrome::{ChromeLayer, ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, Registry};
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+
+type RecordingLayer = reload::Handle<Option<ChromeLayer<Registry>>, Registry>;
+
+/// Installed as the global subscriber the first time a recording starts,
+/// None if another global subscriber was installed before
+static RECORDING_LAYER: OnceLock<Option<RecordingLayer>> = OnceLock::new();
+/// The trace file is written until the guard is dropped
+static RECORDING: Mutex<Option<FlushGuard>> = Mutex::new(None);
+
+fn recording_layer() -> Option<&'static RecordingLayer> {
+    RECORDING_LAYER
+        .get_or_init(|| {
+            let (layer, handle) = reload::Layer::new(None);
+            match tracing::subscriber::set_global_default(
+                tracing_subscriber::registry().with(layer),
+            ) {
+                Ok(()) => Some(handle),
+                Err(error) => {
+                    error!("Failed to install the trace recorder: {}", error);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Record the spans of event conversion, action handling and callbacks to a file in the
+/// chrome trace format, viewable in `chrome://tracing` or Perfetto.
+/// A recording in progress is finished first
+pub fn start_trace_recording(path: &str) -> anyhow::Result<()> {
+    let recording_layer =
+        recording_layer().ok_or_else(|| anyhow!("The trace recorder is not installed"))?;
+    let (layer, guard) = ChromeLayerBuilder::new()
+        .file(path)
+        .include_args(true)
+        .build();
+    recording_layer.reload(Some(layer))?;
+    RECORDING.lock().replace(guard);
+    Ok(())
+}
+
+/// Finish the recording in progress and flush the trace file
+pub fn stop_trace_recording() -> anyhow::Result<()> {
+    if let Some(recording_layer) = recording_layer() {
+        recording_layer.reload(None)?;
+    }
+    drop(RECORDING.lock().take());
+    Ok(())
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_trace_recording_start(path: *mut ValueBox<StringBox>) -> bool {
+    path.with_ref(|path| start_trace_recording(path.as_str()).map_err(|error| error.into()))
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_trace_recording_stop() {
+    if let Err(error) = stop_trace_recording() {
+        error!("Failed to stop the trace recording: {}", error);
+    }
+}
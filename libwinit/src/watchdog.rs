@@ -0,0 +1,145 @@
+use crate::{CallbackRegistry, WinitEventType};
+use std::os::raw::c_void;
+use std::time::{Duration, Instant};
+
+/// Watches the length of the event queue to detect a host that stopped popping events.
+/// Once the queue stays above the threshold for longer than the timeout the queue is considered
+/// stalled: the host is notified and, optionally, input events are dropped while lifecycle
+/// events are still enqueued.
+#[derive(Debug)]
+pub struct EventQueueWatchdog {
+    threshold: usize,
+    timeout: Duration,
+    drop_input_events: bool,
+    callback: Option<EventQueueWatchdogCallback>,
+    overflowing_since: Option<Instant>,
+    is_stalled: bool,
+}
+
+impl EventQueueWatchdog {
+    pub fn new(threshold: usize, timeout: Duration, drop_input_events: bool) -> Self {
+        Self {
+            threshold,
+            timeout,
+            drop_input_events,
+            callback: None,
+            overflowing_since: None,
+            is_stalled: false,
+        }
+    }
+
+    pub fn with_callback(mut self, callback: EventQueueWatchdogCallback) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    /// Update the watchdog state with the current queue length.
+    /// Returns a notification to deliver to the host (outside of the queue lock)
+    /// if the stalled state changed.
+    pub fn check(&mut self, queue_length: usize) -> Option<EventQueueWatchdogNotification> {
+        if queue_length < self.threshold {
+            self.overflowing_since = None;
+            return self.set_stalled(false, queue_length);
+        }
+
+        let overflowing_since = *self.overflowing_since.get_or_insert_with(Instant::now);
+        if overflowing_since.elapsed() >= self.timeout {
+            return self.set_stalled(true, queue_length);
+        }
+        None
+    }
+
+    /// Return true if an event of a given type should not be enqueued
+    pub fn should_drop(&self, event_type: WinitEventType) -> bool {
+        self.is_stalled && self.drop_input_events && event_type.is_input()
+    }
+
+    fn set_stalled(
+        &mut self,
+        is_stalled: bool,
+        queue_length: usize,
+    ) -> Option<EventQueueWatchdogNotification> {
+        if self.is_stalled == is_stalled {
+            return None;
+        }
+        self.is_stalled = is_stalled;
+
+        if is_stalled {
+            warn!(
+                "Event queue is stalled with {} pending events",
+                queue_length
+            );
+        } else {
+            info!("Event queue recovered with {} pending events", queue_length);
+        }
+
+        self.callback
+            .as_ref()
+            .map(|callback| EventQueueWatchdogNotification {
+                callback: callback.clone(),
+                queue_length,
+                is_stalled,
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventQueueWatchdogCallback {
+    thunk: *const c_void,
+    token: usize,
+    callback: unsafe extern "C" fn(*const c_void, usize, bool),
+}
+
+unsafe impl Send for EventQueueWatchdogCallback {}
+unsafe impl Sync for EventQueueWatchdogCallback {}
+
+impl EventQueueWatchdogCallback {
+    pub fn new(
+        callback: unsafe extern "C" fn(*const c_void, usize, bool),
+        thunk: *const c_void,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventQueueWatchdogNotification {
+    callback: EventQueueWatchdogCallback,
+    queue_length: usize,
+    is_stalled: bool,
+}
+
+impl EventQueueWatchdogNotification {
+    pub fn notify(self) {
+        if !CallbackRegistry::is_valid(self.callback.token) {
+            return;
+        }
+        unsafe {
+            (self.callback.callback)(self.callback.thunk, self.queue_length, self.is_stalled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stalls_above_threshold_and_recovers() {
+        let mut watchdog = EventQueueWatchdog::new(10, Duration::ZERO, true);
+
+        assert!(watchdog.check(5).is_none());
+        assert!(!watchdog.should_drop(WinitEventType::WindowEventCursorMoved));
+
+        watchdog.check(10);
+        assert!(watchdog.should_drop(WinitEventType::WindowEventCursorMoved));
+        assert!(!watchdog.should_drop(WinitEventType::WindowEventCloseRequested));
+
+        watchdog.check(3);
+        assert!(!watchdog.should_drop(WinitEventType::WindowEventCursorMoved));
+    }
+}
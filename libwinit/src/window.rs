@@ -1,21 +1,32 @@
 use crate::{
-    ApplicationAction, ApplicationHandle, RequestWindowSurfaceSizeAction, WinitCursorIcon,
+    physical_key_scan_code, with_acknowledgement, ActionAcknowledgement, ApplicationAction,
+    ApplicationHandle, ApplyWindowConfigAction, CallbackRegistry, ClickCounter, FocusWindowAction,
+    RecreateWindowAction, RequestWindowSurfaceSizeAction, ReturnErrorCode, ScreenArea,
+    WindowConfig, WinitCallbackKind, WinitCursorIcon, WinitDropPayloadType, WinitError,
+    WinitErrorCode, WinitEventMouseButton, WinitModifiers, WinitWindowState,
 };
 use geometry_box::{PointBox, SizeBox};
 use parking_lot::Mutex;
 use raw_window_handle_extensions::{VeryRawDisplayHandle, VeryRawWindowHandle};
+use std::collections::HashSet;
 use std::error::Error;
 use std::os::raw::c_void;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 use string_box::StringBox;
 use value_box::{BoxerError, ReturnBoxerResult, ValueBox, ValueBoxPointer};
 use winit::cursor::{Cursor, CursorIcon};
-use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
-use winit::monitor::MonitorHandle;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
+use winit::error::RequestError;
+use winit::event::{ElementState, KeyEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::icon::Icon;
+use winit::monitor::{Fullscreen, MonitorHandle};
 use winit::raw_window_handle::{
     HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle,
 };
-use winit::window::{Window, WindowId};
+use winit::window::{Window, WindowAttributes, WindowId};
 
 #[derive(Debug, Clone)]
 pub struct WindowHandle {
@@ -26,17 +37,44 @@ pub struct WindowHandle {
 }
 
 impl WindowHandle {
-    pub fn for_window(application_handle: &ApplicationHandle, window: Box<dyn Window>) -> Self {
+    pub fn for_window(
+        application_handle: &ApplicationHandle,
+        window: Box<dyn Window>,
+        window_attributes: WindowAttributes,
+    ) -> Self {
         Self {
             id: window.id(),
             data: Arc::new(Mutex::new(WindowData {
                 outer_position: window
                     .outer_position()
                     .unwrap_or_else(|_| PhysicalPosition::default()),
+                pointer_position: Default::default(),
+                click_counter: Default::default(),
                 surface_size: window.surface_size(),
                 scale_factor: window.scale_factor(),
+                modifiers: Default::default(),
+                is_maximized: window.is_maximized(),
+                window_state: window_state(window.as_ref()),
+                monitor: window.current_monitor(),
+                expected_resize_cause: None,
+                ime_caret_area: None,
+                pressed_keys: Default::default(),
+                pressed_buttons: 0,
+                window_attributes,
+                accepted_drop_types: WinitDropPayloadType::Files.mask(),
+                dragged_paths: vec![],
+                listener_ids: Default::default(),
+                last_listener_id: 0,
                 window_redraw_listeners: vec![],
                 window_resize_listeners: vec![],
+                window_focus_listeners: vec![],
+                window_close_listeners: vec![],
+                window_scale_factor_listeners: vec![],
+                nested_resizes: None,
+                nested_redraws: None,
+                nested_scale_factor_changes: None,
+                nested_focus_changes: None,
+                has_deferred_redraw: false,
             })),
             window: Arc::from(Mutex::new(Some(window))),
             application_handle: application_handle.clone(),
@@ -47,14 +85,115 @@ impl WindowHandle {
         self.id
     }
 
-    pub fn request_surface_size(&self, surface_size: Size) {
-        self.application_handle
-            .enqueue_action(ApplicationAction::RequestWindowSurfaceSize(
-                RequestWindowSurfaceSizeAction {
-                    surface_size,
-                    window_id: self.id,
-                },
-            ))
+    /// The id of the current native window, it differs from the window id once the window
+    /// is recreated
+    pub fn native_id(&self) -> Option<WindowId> {
+        self.window.lock().as_ref().map(|window| window.id())
+    }
+
+    pub fn application_handle(&self) -> &ApplicationHandle {
+        &self.application_handle
+    }
+
+    pub fn request_recreate(
+        &self,
+        acknowledgement: Option<ActionAcknowledgement>,
+    ) -> Result<(), WinitError> {
+        self.application_handle.enqueue_acknowledged_action(
+            ApplicationAction::RecreateWindow(RecreateWindowAction { window_id: self.id }),
+            acknowledgement,
+        )
+    }
+
+    /// Replace a lost native window with a new one created from the same attributes.
+    /// The window keeps its id, returns the id of the new native window.
+    /// Must be called from a UI thread
+    pub fn recreate(&self, event_loop: &dyn ActiveEventLoop) -> Result<WindowId, RequestError> {
+        let window_attributes = self.data.lock().window_attributes.clone();
+        let window = event_loop.create_window(window_attributes)?;
+        window.set_ime_allowed(true);
+        #[cfg(windows_platform)]
+        self.application_handle.hook_window(window.as_ref());
+        let native_id = window.id();
+
+        {
+            let mut lock = self.data.lock();
+            lock.outer_position = window
+                .outer_position()
+                .unwrap_or_else(|_| PhysicalPosition::default());
+            lock.surface_size = window.surface_size();
+            lock.scale_factor = window.scale_factor();
+            lock.is_maximized = window.is_maximized();
+            lock.window_state = window_state(window.as_ref());
+            lock.monitor = window.current_monitor();
+            lock.expected_resize_cause = None;
+        }
+
+        // the lost window is dropped outside of the lock
+        let lost_window = self.window.lock().replace(window);
+        drop(lost_window);
+        Ok(native_id)
+    }
+
+    pub fn request_surface_size(
+        &self,
+        surface_size: Size,
+        acknowledgement: Option<ActionAcknowledgement>,
+    ) -> Result<(), WinitError> {
+        self.application_handle.enqueue_acknowledged_action(
+            ApplicationAction::RequestWindowSurfaceSize(RequestWindowSurfaceSizeAction {
+                surface_size,
+                window_id: self.id,
+            }),
+            acknowledgement,
+        )
+    }
+
+    pub fn enqueue_config(
+        &self,
+        config: WindowConfig,
+        acknowledgement: Option<ActionAcknowledgement>,
+    ) -> Result<(), WinitError> {
+        self.application_handle.enqueue_acknowledged_action(
+            ApplicationAction::ApplyWindowConfig(ApplyWindowConfigAction {
+                config,
+                window_id: self.id,
+            }),
+            acknowledgement,
+        )
+    }
+
+    /// Must be called from a UI thread
+    pub fn apply_config(&self, config: WindowConfig) {
+        // the setters take the window lock and keep the values for when it is recreated
+        if let Some(resizable) = config.resizable {
+            self.set_resizable(resizable);
+        }
+        if config.min_surface_size.is_some() {
+            self.set_min_surface_size(config.min_surface_size);
+        }
+        if config.max_surface_size.is_some() {
+            self.set_max_surface_size(config.max_surface_size);
+        }
+        if let Some(window) = self.window.lock().as_ref() {
+            if let Some(title) = config.title {
+                window.set_title(title.as_str());
+                self.remember_title(title.as_str());
+            }
+            if let Some(decorations) = config.decorations {
+                window.set_decorations(decorations);
+            }
+            if let Some(level) = config.level {
+                window.set_window_level(level);
+            }
+            if let Some(surface_size) = config.surface_size {
+                self.expect_resize(WinitResizeCause::Program, surface_size);
+                let _ = window.request_surface_size(surface_size);
+            }
+            if let Some(cursor) = config.cursor {
+                window.set_cursor(cursor.into());
+            }
+        }
     }
 
     pub fn on_window_resized(&self, size: &PhysicalSize<u32>) {
@@ -64,10 +203,87 @@ impl WindowHandle {
             return;
         }
 
+        self.data.lock().surface_size = size.clone();
+
+        // listeners are called without holding the lock, so that they can call back into
+        // the window, for example to query its size or to request a new one
+        self.dispatch(
+            *size,
+            |data| {
+                (
+                    &mut data.window_resize_listeners,
+                    &mut data.nested_resizes,
+                    &data.listener_ids,
+                )
+            },
+            |listener, size| {
+                self.application_handle.call_within_budget(
+                    WinitCallbackKind::Resize,
+                    listener.token,
+                    Some(self.id),
+                    || listener.on_window_resized(size),
+                )
+            },
+        );
+    }
+
+    /// Query the state of the window after an event that may have changed it and return the
+    /// previous and the new state if they differ.
+    /// Must be called from a UI thread
+    pub fn update_window_state(&self) -> Option<(WinitWindowState, WinitWindowState)> {
+        let state = self
+            .window
+            .lock()
+            .as_ref()
+            .map(|window| window_state(window.as_ref()))?;
+        let previous_state = std::mem::replace(&mut self.data.lock().window_state, state);
+        (previous_state != state).then_some((previous_state, state))
+    }
+
+    /// Query the monitor the window is on after it moved or its scale factor changed and
+    /// return the new monitor if the window is now on a different one.
+    /// Must be called from a UI thread
+    pub fn update_monitor(&self) -> Option<MonitorHandle> {
+        let monitor = self
+            .window
+            .lock()
+            .as_ref()
+            .and_then(|window| window.current_monitor())?;
+        let previous_monitor = self.data.lock().monitor.replace(monitor.clone());
+        (previous_monitor.as_ref() != Some(&monitor)).then_some(monitor)
+    }
+
+    /// Remember the cause of the upcoming resize to the given size. Platforms do not resize
+    /// the window to its current size, such a request clears the expected cause instead
+    pub fn expect_resize(&self, cause: WinitResizeCause, surface_size: Size) {
         let mut lock = self.data.lock();
-        lock.surface_size = size.clone();
-        for listener in &lock.window_resize_listeners {
-            listener.on_window_resized(size);
+        let surface_size: PhysicalSize<u32> = surface_size.to_physical(lock.scale_factor);
+        lock.expected_resize_cause = (surface_size != lock.surface_size).then_some(cause);
+    }
+
+    /// Must be called from a UI thread when the window is resized.
+    /// Maximizing and restoring are detected by comparing with the previous maximized state,
+    /// otherwise the resize is attributed to the last expected cause, or to the user.
+    pub fn take_resize_cause(&self) -> WinitResizeCause {
+        let is_maximized = self
+            .window
+            .lock()
+            .as_ref()
+            .map(|window| window.is_maximized());
+
+        let mut lock = self.data.lock();
+        let expected_cause = lock.expected_resize_cause.take();
+
+        match is_maximized {
+            Some(is_maximized) if is_maximized != lock.is_maximized => {
+                lock.is_maximized = is_maximized;
+                if is_maximized {
+                    WinitResizeCause::Maximized
+                } else {
+                    WinitResizeCause::Restored
+                }
+            }
+            _ => expected_cause.unwrap_or_default(),
         }
     }
 
@@ -76,13 +292,212 @@ impl WindowHandle {
         lock.outer_position = position.clone();
     }
 
+    /// Must be called from a UI thread when the pointer moves or presses a button
+    pub fn on_pointer_moved(&self, position: &PhysicalPosition<f64>) {
+        self.data.lock().pointer_position = position.clone();
+    }
+
+    /// The click count of a mouse button press or release, 0 if click counting is disabled.
+    /// Must be called from a UI thread for every mouse button event in order
+    pub fn count_click(
+        &self,
+        button: WinitEventMouseButton,
+        state: ElementState,
+        position: PhysicalPosition<f64>,
+    ) -> u32 {
+        let settings = self.application_handle.settings().clicks;
+        if !settings.is_enabled {
+            return 0;
+        }
+        let mut lock = self.data.lock();
+        match state {
+            ElementState::Pressed => {
+                lock.click_counter
+                    .press(button, position, Instant::now(), &settings)
+            }
+            ElementState::Released => lock.click_counter.release(button),
+        }
+    }
+
+    /// The last known position of the pointer within the window
+    pub fn pointer_position(&self) -> PhysicalPosition<f64> {
+        self.data.lock().pointer_position
+    }
+
     pub fn on_window_redraw(&self) {
-        let lock = self.data.lock();
-        for listener in &lock.window_redraw_listeners {
-            listener.on_redraw_requested();
+        // see on_window_resized
+        self.dispatch(
+            (),
+            |data| {
+                (
+                    &mut data.window_redraw_listeners,
+                    &mut data.nested_redraws,
+                    &data.listener_ids,
+                )
+            },
+            |listener, _| {
+                self.application_handle.call_within_budget(
+                    WinitCallbackKind::Redraw,
+                    listener.token,
+                    Some(self.id),
+                    || listener.on_redraw_requested(),
+                )
+            },
+        );
+    }
+
+    /// The physical surface size that keeps the logical size of the window for the new scale
+    pub fn scaled_surface_size(&self, scale_factor: f64) -> PhysicalSize<u32> {
+        let current_logical_size: LogicalSize<f64> =
+            self.surface_size().to_logical(self.scale_factor());
+        current_logical_size.to_physical(scale_factor)
+    }
+
+    /// Must be called from a UI thread when the scale factor of the window changes,
+    /// before the surface is resized to the given size
+    pub fn on_scale_factor_changed(&self, scale_factor: f64, size: &PhysicalSize<u32>) {
+        // see on_window_resized
+        self.dispatch(
+            (scale_factor, *size),
+            |data| {
+                (
+                    &mut data.window_scale_factor_listeners,
+                    &mut data.nested_scale_factor_changes,
+                    &data.listener_ids,
+                )
+            },
+            |listener, (scale_factor, size)| {
+                self.application_handle.call_within_budget(
+                    WinitCallbackKind::ScaleFactor,
+                    listener.token,
+                    Some(self.id),
+                    || listener.on_scale_factor_changed(*scale_factor, size),
+                )
+            },
+        );
+    }
+
+    /// Must be called from a UI thread when the window gains or loses the focus
+    pub fn on_window_focused(&self, is_focused: bool) {
+        // see on_window_resized
+        self.dispatch(
+            is_focused,
+            |data| {
+                (
+                    &mut data.window_focus_listeners,
+                    &mut data.nested_focus_changes,
+                    &data.listener_ids,
+                )
+            },
+            |listener, is_focused| {
+                self.application_handle.call_within_budget(
+                    WinitCallbackKind::Focus,
+                    listener.token,
+                    Some(self.id),
+                    || listener.on_focus_changed(*is_focused),
+                )
+            },
+        );
+    }
+
+    /// Call the listeners selected from the window data without holding the lock.
+    /// While they are called the listeners are taken out of the window data, so events of the
+    /// same kind raised meanwhile, such as a nested resize sent by the platform while a resize
+    /// listener runs, are queued and replayed before the listeners are put back
+    fn dispatch<T, E>(
+        &self,
+        event: E,
+        select: fn(&mut WindowData) -> WindowListenersOf<'_, T, E>,
+        call: impl Fn(&T, &E),
+    ) {
+        let listeners = {
+            let data = &mut *self.data.lock();
+            let (listeners, nested_events, _) = select(data);
+            if let Some(nested_events) = nested_events {
+                nested_events.push(event);
+                return;
+            }
+            *nested_events = Some(vec![]);
+            std::mem::take(listeners)
+        };
+
+        let mut events = vec![event];
+        loop {
+            for event in &events {
+                for (id, listener) in &listeners {
+                    if self.is_listening(*id) {
+                        call(listener, event);
+                    }
+                }
+            }
+
+            let data = &mut *self.data.lock();
+            let (current_listeners, nested_events, listener_ids) = select(data);
+            events = nested_events.take().unwrap_or_default();
+            if events.is_empty() {
+                restore_listeners(listeners, current_listeners, listener_ids);
+                return;
+            }
+            *nested_events = Some(vec![]);
         }
     }
 
+    /// Must be called from a UI thread when closing the window is requested.
+    /// Without close listeners the host decides by handling the close requested event,
+    /// otherwise the window is closed unless one of the listeners vetoes it.
+    pub fn on_close_requested(&self) {
+        let listeners = std::mem::take(&mut self.data.lock().window_close_listeners);
+
+        // see on_window_resized
+        let mut should_close = None;
+        for (id, listener) in &listeners {
+            if !self.is_listening(*id) {
+                continue;
+            }
+            let allows_close = self.application_handle.call_within_budget(
+                WinitCallbackKind::CloseRequested,
+                listener.token,
+                Some(self.id),
+                || listener.on_close_requested(),
+            );
+            if let Some(allows_close) = allows_close {
+                should_close = Some(should_close.unwrap_or(true) && allows_close);
+            }
+        }
+
+        {
+            let data = &mut *self.data.lock();
+            restore_listeners(
+                listeners,
+                &mut data.window_close_listeners,
+                &data.listener_ids,
+            );
+        }
+
+        if should_close == Some(true) {
+            self.close_window();
+        }
+    }
+
+    /// Declare which kinds of dragged content the window accepts, given as a mask of
+    /// [`WinitDropPayloadType`]. Only files are accepted by default
+    pub fn set_accepted_drop_types(&self, payload_types: u32) {
+        self.data.lock().accepted_drop_types = payload_types;
+    }
+
+    pub fn accepts_drop(&self, payload_type: WinitDropPayloadType) -> bool {
+        self.data.lock().accepted_drop_types & payload_type.mask() != 0
+    }
+
+    /// The paths of the drag in progress, drag moves do not repeat them
+    pub fn dragged_paths(&self) -> Vec<PathBuf> {
+        self.data.lock().dragged_paths.clone()
+    }
+
+    pub fn set_dragged_paths(&self, paths: Vec<PathBuf>) {
+        self.data.lock().dragged_paths = paths;
+    }
+
     pub fn surface_size(&self) -> PhysicalSize<u32> {
         self.data.lock().surface_size
     }
@@ -95,12 +510,120 @@ impl WindowHandle {
         self.data.lock().outer_position
     }
 
+    pub fn modifiers(&self) -> WinitModifiers {
+        self.data.lock().modifiers
+    }
+
+    pub fn set_modifiers(&self, modifiers: WinitModifiers) {
+        self.data.lock().modifiers = modifiers;
+    }
+
+    pub fn initial_state(&self) -> WinitWindowInitialState {
+        let lock = self.data.lock();
+        WinitWindowInitialState {
+            scale_factor: lock.scale_factor,
+            x: lock.outer_position.x,
+            y: lock.outer_position.y,
+            width: lock.surface_size.width,
+            height: lock.surface_size.height,
+        }
+    }
+
+    /// Requested right away, or once the event loop is about to wait while redraws are
+    /// throttled, see [`crate::RedrawThrottle`]
+    pub fn request_redraw(&self) -> anyhow::Result<()> {
+        let window = self.window.lock();
+        let window = window.as_ref().ok_or_else(|| anyhow!("Window is closed"))?;
+        if self.application_handle.throttles_redraws() {
+            self.data.lock().has_deferred_redraw = true;
+        } else {
+            window.request_redraw();
+        }
+        Ok(())
+    }
+
+    pub fn has_deferred_redraw(&self) -> bool {
+        self.data.lock().has_deferred_redraw
+    }
+
+    pub fn request_deferred_redraw(&self) {
+        if std::mem::take(&mut self.data.lock().has_deferred_redraw) {
+            if let Some(window) = self.window.lock().as_ref() {
+                window.request_redraw();
+            }
+        }
+    }
+
+    /// None if the platform can not tell
+    pub fn is_minimized(&self) -> Option<bool> {
+        self.window
+            .lock()
+            .as_ref()
+            .and_then(|window| window.is_minimized())
+    }
+
+    /// The area covered by the window including its decorations,
+    /// None if the platform does not expose the position of windows
+    pub fn outer_area(&self) -> Option<ScreenArea> {
+        self.window.lock().as_ref().and_then(|window| {
+            Some(ScreenArea {
+                position: window.outer_position().ok()?,
+                size: window.outer_size(),
+            })
+        })
+    }
+
     pub fn set_outer_position(&self, position: Position) {
         if let Some(window) = self.window.lock().as_ref() {
             window.set_outer_position(position);
         }
     }
 
+    /// Show the icon in the title bar and the task bar, a recreated window keeps it.
+    /// macOS windows do not have icons of their own
+    pub fn set_window_icon(&self, icon: Icon) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_window_icon(Some(icon.clone()));
+        }
+        let mut lock = self.data.lock();
+        let window_attributes = std::mem::take(&mut lock.window_attributes);
+        lock.window_attributes = window_attributes.with_window_icon(Some(icon));
+    }
+
+    pub fn set_resizable(&self, is_resizable: bool) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_resizable(is_resizable);
+        }
+        // kept for when the window is recreated
+        self.data.lock().window_attributes.resizable = is_resizable;
+    }
+
+    /// False once the window is closed
+    pub fn is_resizable(&self) -> bool {
+        self.window
+            .lock()
+            .as_ref()
+            .map(|window| window.is_resizable())
+            .unwrap_or(false)
+    }
+
+    /// The window can not be resized below the size, None removes the constraint
+    pub fn set_min_surface_size(&self, size: Option<Size>) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_min_surface_size(size);
+        }
+        // kept for when the window is recreated
+        self.data.lock().window_attributes.min_surface_size = size;
+    }
+
+    /// The window can not be resized above the size, None removes the constraint
+    pub fn set_max_surface_size(&self, size: Option<Size>) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_max_surface_size(size);
+        }
+        self.data.lock().window_attributes.max_surface_size = size;
+    }
+
     pub fn set_cursor(&self, cursor: impl Into<Cursor>) {
         if let Some(window) = self.window.lock().as_ref() {
             window.set_cursor(cursor.into());
@@ -111,18 +634,184 @@ impl WindowHandle {
         if let Some(window) = self.window.lock().as_ref() {
             window.set_title(title.as_ref());
         }
+        self.remember_title(title.as_ref());
+    }
+
+    /// A recreated window keeps the title set after the window was created
+    fn remember_title(&self, title: &str) {
+        let mut lock = self.data.lock();
+        let window_attributes = std::mem::take(&mut lock.window_attributes);
+        lock.window_attributes = window_attributes.with_title(title);
     }
 
+    /// Show the title of a document with a marker when it has unsaved changes.
+    /// On macOS the window is also marked as edited, which shows a dot in its close button
+    pub fn set_document_state(&self, title: &str, is_modified: bool) {
+        let title = document_title(title, is_modified);
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_title(title.as_str());
+            #[cfg(macos_platform)]
+            {
+                use winit::platform::macos::WindowExtMacOS;
+                window.set_document_edited(is_modified);
+            }
+        }
+        self.remember_title(title.as_str());
+    }
+
+    /// Some platforms, such as Wayland, can not read the title back from the window,
+    /// the title it was created with or last set through the handle is returned instead.
+    /// None once the window is closed
     pub fn get_title(&self) -> Option<String> {
-        self.window.lock().as_ref().map(|window| window.title())
+        let title = self.window.lock().as_ref().map(|window| window.title())?;
+        if title.is_empty() {
+            Some(self.data.lock().window_attributes.title.clone())
+        } else {
+            Some(title)
+        }
+    }
+
+    /// Must be called from a UI thread for every keyboard event of the window
+    pub fn on_key_input(&self, event: &KeyEvent) {
+        let scan_code = physical_key_scan_code(event.physical_key);
+        // unidentified keys all share the code 0, they can not be told apart
+        if scan_code == 0 {
+            return;
+        }
+        let mut lock = self.data.lock();
+        match event.state {
+            ElementState::Pressed => lock.pressed_keys.insert(scan_code),
+            ElementState::Released => lock.pressed_keys.remove(&scan_code),
+        };
+    }
+
+    /// Keys released while the window is not focused are not reported,
+    /// so all keys are considered released when the window loses focus
+    pub fn release_pressed_keys(&self) {
+        self.data.lock().pressed_keys.clear();
+    }
+
+    pub fn is_key_pressed(&self, scan_code: u32) -> bool {
+        self.data.lock().pressed_keys.contains(&scan_code)
+    }
+
+    /// Track the mouse buttons held down within the window and return the mask of the buttons
+    /// held after the press or release, with a bit for each button code.
+    /// Buttons with a code of 32 and above are not tracked
+    pub fn update_pressed_buttons(
+        &self,
+        button: WinitEventMouseButton,
+        state: ElementState,
+    ) -> u32 {
+        let bit = 1u32.checked_shl(button.code() as u32).unwrap_or(0);
+        let mut lock = self.data.lock();
+        match state {
+            ElementState::Pressed => lock.pressed_buttons |= bit,
+            ElementState::Released => lock.pressed_buttons &= !bit,
+        };
+        lock.pressed_buttons
     }
 
-    pub fn add_redraw_listener(&self, listener: WindowRedrawRequestedListener) {
-        self.data.lock().window_redraw_listeners.push(listener);
+    pub fn pressed_buttons(&self) -> u32 {
+        self.data.lock().pressed_buttons
+    }
+
+    /// Remember the caret area of the host, applied later from the UI thread
+    pub fn post_ime_caret_area(&self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) {
+        self.data.lock().ime_caret_area = Some((position, size));
+        // applied before the event loop waits, which an idle event loop would not get to
+        self.application_handle.wake_up();
+    }
+
+    /// Must be called from a UI thread
+    pub fn apply_ime_caret_area(&self) {
+        let ime_caret_area = self.data.lock().ime_caret_area.take();
+        if let Some((position, size)) = ime_caret_area {
+            if let Some(window) = self.window.lock().as_ref() {
+                window.set_ime_cursor_area(Position::Physical(position), Size::Physical(size));
+            }
+        }
+    }
+
+    pub fn add_redraw_listener(&self, listener: WindowRedrawRequestedListener) -> usize {
+        let mut lock = self.data.lock();
+        let id = lock.register_listener();
+        lock.window_redraw_listeners.push((id, listener));
+        id
+    }
+
+    pub fn add_resized_listener(&self, listener: WindowResizedListener) -> usize {
+        let mut lock = self.data.lock();
+        let id = lock.register_listener();
+        lock.window_resize_listeners.push((id, listener));
+        id
+    }
+
+    pub fn add_focus_listener(&self, listener: WindowFocusListener) -> usize {
+        let mut lock = self.data.lock();
+        let id = lock.register_listener();
+        lock.window_focus_listeners.push((id, listener));
+        id
+    }
+
+    pub fn add_close_listener(&self, listener: WindowCloseRequestedListener) -> usize {
+        let mut lock = self.data.lock();
+        let id = lock.register_listener();
+        lock.window_close_listeners.push((id, listener));
+        id
+    }
+
+    pub fn add_scale_factor_listener(&self, listener: WindowScaleFactorChangedListener) -> usize {
+        let mut lock = self.data.lock();
+        let id = lock.register_listener();
+        lock.window_scale_factor_listeners.push((id, listener));
+        id
+    }
+
+    /// Returns the ids of the added listeners, redraw listeners first
+    pub fn add_listeners(&self, listeners: WindowListeners) -> Vec<usize> {
+        let mut ids = Vec::with_capacity(listeners.redraw.len() + listeners.resize.len());
+        for listener in listeners.redraw {
+            ids.push(self.add_redraw_listener(listener));
+        }
+        for listener in listeners.resize {
+            ids.push(self.add_resized_listener(listener));
+        }
+        ids
+    }
+
+    /// Listeners removed by a previously called listener of the same event are skipped
+    fn is_listening(&self, id: usize) -> bool {
+        self.data.lock().listener_ids.contains(&id)
+    }
+
+    /// Unregister a listener by the id returned when it was added, so that its thunk is no
+    /// longer referenced. Returns false if there is no such listener
+    pub fn remove_listener(&self, id: usize) -> bool {
+        let mut lock = self.data.lock();
+        if !lock.listener_ids.remove(&id) {
+            return false;
+        }
+        // listeners being called right now are dropped when they are put back
+        lock.window_redraw_listeners
+            .retain(|(listener_id, _)| *listener_id != id);
+        lock.window_resize_listeners
+            .retain(|(listener_id, _)| *listener_id != id);
+        lock.window_focus_listeners
+            .retain(|(listener_id, _)| *listener_id != id);
+        lock.window_close_listeners
+            .retain(|(listener_id, _)| *listener_id != id);
+        lock.window_scale_factor_listeners
+            .retain(|(listener_id, _)| *listener_id != id);
+        true
     }
 
-    pub fn add_resized_listener(&self, listener: WindowResizedListener) {
-        self.data.lock().window_resize_listeners.push(listener);
+    /// Focus the window from any thread, ahead of the queued background actions
+    pub fn request_focus(&self) {
+        self.application_handle
+            .enqueue_action(ApplicationAction::FocusWindow(FocusWindowAction {
+                window_id: self.id,
+            }))
     }
 
     pub fn focus_window(&self) {
@@ -131,6 +820,27 @@ impl WindowHandle {
         }
     }
 
+    /// Cover the monitor without changing its video mode, None for the monitor the window is on
+    pub fn set_fullscreen_borderless(&self, monitor: Option<MonitorHandle>) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+    }
+
+    pub fn exit_fullscreen(&self) {
+        if let Some(window) = self.window.lock().as_ref() {
+            window.set_fullscreen(None);
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.window
+            .lock()
+            .as_ref()
+            .map(|window| window.fullscreen().is_some())
+            .unwrap_or(false)
+    }
+
     pub fn current_monitor(&self) -> Option<MonitorHandle> {
         self.window
             .lock()
@@ -169,27 +879,166 @@ impl WindowHandle {
     }
 }
 
+/// Window properties captured when the window is created, delivered together with the window
+/// handle so that the host does not need to query them one by one.
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct WinitWindowInitialState {
+    pub scale_factor: f64,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
 #[derive(Debug)]
 struct WindowData {
     outer_position: PhysicalPosition<i32>,
+    pointer_position: PhysicalPosition<f64>,
+    click_counter: ClickCounter,
     surface_size: PhysicalSize<u32>,
     scale_factor: f64,
-    window_redraw_listeners: Vec<WindowRedrawRequestedListener>,
-    window_resize_listeners: Vec<WindowResizedListener>,
+    modifiers: WinitModifiers,
+    is_maximized: bool,
+    /// The state last reported to the host
+    window_state: WinitWindowState,
+    /// The monitor the window was last seen on
+    monitor: Option<MonitorHandle>,
+    expected_resize_cause: Option<WinitResizeCause>,
+    /// Posted by the host and not yet applied
+    ime_caret_area: Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
+    /// Scan codes of the keys currently held down while the window is focused
+    pressed_keys: HashSet<u32>,
+    /// Mask of the mouse buttons currently held down within the window
+    pressed_buttons: u32,
+    /// The attributes the window was created with, to be able to recreate it
+    window_attributes: WindowAttributes,
+    /// Mask of the [`WinitDropPayloadType`] that can be dropped onto the window
+    accepted_drop_types: u32,
+    /// Paths of the drag that is hovering the window
+    dragged_paths: Vec<PathBuf>,
+    /// Ids of the registered listeners of all kinds
+    listener_ids: HashSet<usize>,
+    last_listener_id: usize,
+    window_redraw_listeners: Vec<(usize, WindowRedrawRequestedListener)>,
+    window_resize_listeners: Vec<(usize, WindowResizedListener)>,
+    window_focus_listeners: Vec<(usize, WindowFocusListener)>,
+    window_close_listeners: Vec<(usize, WindowCloseRequestedListener)>,
+    window_scale_factor_listeners: Vec<(usize, WindowScaleFactorChangedListener)>,
+    /// Events raised while the listeners of their kind are called, None when they are not
+    nested_resizes: Option<Vec<PhysicalSize<u32>>>,
+    nested_redraws: Option<Vec<()>>,
+    nested_scale_factor_changes: Option<Vec<(f64, PhysicalSize<u32>)>>,
+    nested_focus_changes: Option<Vec<bool>>,
+    /// Requested by the host and held back by the redraw throttle
+    has_deferred_redraw: bool,
+}
+
+/// The listeners of one kind, the events raised while they are called and the ids of
+/// the listeners that are still registered
+type WindowListenersOf<'a, T, E> = (
+    &'a mut Vec<(usize, T)>,
+    &'a mut Option<Vec<E>>,
+    &'a HashSet<usize>,
+);
+
+impl WindowData {
+    /// Listener ids start from 1, so that 0 can tell the host that no listener was added
+    fn register_listener(&mut self) -> usize {
+        self.last_listener_id += 1;
+        self.listener_ids.insert(self.last_listener_id);
+        self.last_listener_id
+    }
+}
+
+/// Platforms that do not report minimization are never considered minimized
+fn window_state(window: &dyn Window) -> WinitWindowState {
+    if window.fullscreen().is_some() {
+        WinitWindowState::Fullscreen
+    } else if window.is_minimized() == Some(true) {
+        WinitWindowState::Minimized
+    } else if window.is_maximized() {
+        WinitWindowState::Maximized
+    } else {
+        WinitWindowState::Normal
+    }
+}
+
+/// The title of a document as each platform usually presents unsaved changes
+fn document_title(title: &str, is_modified: bool) -> String {
+    if !is_modified {
+        title.to_string()
+    } else if cfg!(macos_platform) {
+        format!("• {}", title)
+    } else {
+        format!("*{}", title)
+    }
+}
+
+/// Put the called listeners back before the ones added while they were called,
+/// unless they were removed meanwhile
+fn restore_listeners<T>(
+    called_listeners: Vec<(usize, T)>,
+    listeners: &mut Vec<(usize, T)>,
+    listener_ids: &HashSet<usize>,
+) {
+    let added_listeners = std::mem::replace(listeners, called_listeners);
+    listeners.retain(|(id, _)| listener_ids.contains(id));
+    listeners.extend(added_listeners);
+}
+
+/// What caused a window to be resized
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum WinitResizeCause {
+    /// The user resized the window, or the cause is unknown
+    User,
+    /// The application requested a new surface size
+    Program,
+    /// The scale factor of the window changed, for example when moved to another monitor
+    ScaleFactor,
+    Maximized,
+    Restored,
+}
+
+impl Default for WinitResizeCause {
+    fn default() -> Self {
+        Self::User
+    }
+}
+
+/// Listeners to register on a window as soon as it is created
+#[derive(Debug, Default)]
+pub struct WindowListeners {
+    pub redraw: Vec<WindowRedrawRequestedListener>,
+    pub resize: Vec<WindowResizedListener>,
 }
 
 #[derive(Debug)]
 pub struct WindowRedrawRequestedListener {
     thunk: *const c_void,
+    token: usize,
     callback: unsafe extern "C" fn(*const c_void),
 }
 
+unsafe impl Send for WindowRedrawRequestedListener {}
+unsafe impl Sync for WindowRedrawRequestedListener {}
+
 impl WindowRedrawRequestedListener {
     pub fn new(callback: unsafe extern "C" fn(*const c_void), thunk: *const c_void) -> Self {
-        Self { callback, thunk }
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
     }
 
     fn on_redraw_requested(&self) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("redraw_listener");
         unsafe {
             (self.callback)(self.thunk);
         }
@@ -199,24 +1048,137 @@ impl WindowRedrawRequestedListener {
 #[derive(Debug)]
 pub struct WindowResizedListener {
     thunk: *const c_void,
+    token: usize,
     callback: unsafe extern "C" fn(*const c_void, u32, u32),
 }
 
+unsafe impl Send for WindowResizedListener {}
+unsafe impl Sync for WindowResizedListener {}
+
 impl WindowResizedListener {
     pub fn new(
         callback: unsafe extern "C" fn(*const c_void, u32, u32),
         thunk: *const c_void,
     ) -> Self {
-        Self { callback, thunk }
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
     }
 
     fn on_window_resized(&self, size: &PhysicalSize<u32>) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("resize_listener");
         unsafe {
             (self.callback)(self.thunk, size.width, size.height);
         }
     }
 }
 
+/// Called on the UI thread as soon as the window gains or loses the focus, before the
+/// focused event is enqueued
+#[derive(Debug)]
+pub struct WindowFocusListener {
+    thunk: *const c_void,
+    token: usize,
+    callback: unsafe extern "C" fn(*const c_void, bool),
+}
+
+unsafe impl Send for WindowFocusListener {}
+unsafe impl Sync for WindowFocusListener {}
+
+impl WindowFocusListener {
+    pub fn new(callback: unsafe extern "C" fn(*const c_void, bool), thunk: *const c_void) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    fn on_focus_changed(&self, is_focused: bool) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("focus_listener");
+        unsafe {
+            (self.callback)(self.thunk, is_focused);
+        }
+    }
+}
+
+/// Called on the UI thread while the scale factor changes with the new scale factor and
+/// surface size, so that the host can resize its surfaces before the next frame
+#[derive(Debug)]
+pub struct WindowScaleFactorChangedListener {
+    thunk: *const c_void,
+    token: usize,
+    callback: unsafe extern "C" fn(*const c_void, f64, u32, u32),
+}
+
+unsafe impl Send for WindowScaleFactorChangedListener {}
+unsafe impl Sync for WindowScaleFactorChangedListener {}
+
+impl WindowScaleFactorChangedListener {
+    pub fn new(
+        callback: unsafe extern "C" fn(*const c_void, f64, u32, u32),
+        thunk: *const c_void,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    fn on_scale_factor_changed(&self, scale_factor: f64, size: &PhysicalSize<u32>) {
+        if !CallbackRegistry::is_valid(self.token) {
+            return;
+        }
+        trace_span!("scale_factor_listener");
+        unsafe {
+            (self.callback)(self.thunk, scale_factor, size.width, size.height);
+        }
+    }
+}
+
+/// Decides on the UI thread whether the window may be closed, returning false keeps it open,
+/// for example to ask the user about unsaved changes
+#[derive(Debug)]
+pub struct WindowCloseRequestedListener {
+    thunk: *const c_void,
+    token: usize,
+    callback: unsafe extern "C" fn(*const c_void) -> bool,
+}
+
+unsafe impl Send for WindowCloseRequestedListener {}
+unsafe impl Sync for WindowCloseRequestedListener {}
+
+impl WindowCloseRequestedListener {
+    pub fn new(
+        callback: unsafe extern "C" fn(*const c_void) -> bool,
+        thunk: *const c_void,
+    ) -> Self {
+        Self {
+            callback,
+            thunk,
+            token: CallbackRegistry::token_of(thunk),
+        }
+    }
+
+    /// None if the listener can no longer be called
+    fn on_close_requested(&self) -> Option<bool> {
+        if !CallbackRegistry::is_valid(self.token) {
+            return None;
+        }
+        trace_span!("close_listener");
+        Some(unsafe { (self.callback)(self.thunk) })
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_handle_get_id(window_handle: *mut ValueBox<WindowHandle>) -> usize {
     window_handle
@@ -299,14 +1261,130 @@ pub extern "C" fn winit_window_handle_request_surface_size(
     window: *mut ValueBox<WindowHandle>,
     width: u32,
     height: u32,
+) -> WinitErrorCode {
+    window
+        .with_ref_ok(|window| {
+            window.request_surface_size(Size::Physical(PhysicalSize::new(width, height)), None)
+        })
+        .map_err(WinitError::from)
+        .and_then(|result| result)
+        .into_error_code()
+}
+
+/// Set the icon of the window from non-premultiplied RGBA pixels, 4 bytes per pixel row by
+/// row. Fails with an invalid argument if the length does not match the size.
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_window_icon(
+    window: *mut ValueBox<WindowHandle>,
+    rgba: *const u8,
+    len: usize,
+    width: u32,
+    height: u32,
+) -> WinitErrorCode {
+    let rgba = if rgba.is_null() || len == 0 {
+        vec![]
+    } else {
+        unsafe { std::slice::from_raw_parts(rgba, len) }.to_vec()
+    };
+    window
+        .with_ref_ok(|window| {
+            Icon::from_rgba(rgba, width, height)
+                .map(|icon| window.set_window_icon(icon))
+                .map_err(WinitError::from)
+        })
+        .map_err(WinitError::from)
+        .and_then(|result| result)
+        .into_error_code()
+}
+
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_resizable(
+    window: *mut ValueBox<WindowHandle>,
+    is_resizable: bool,
+) {
+    window
+        .with_ref_ok(|window| window.set_resizable(is_resizable))
+        .log();
+}
+
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_is_resizable(window: *mut ValueBox<WindowHandle>) -> bool {
+    window
+        .with_ref_ok(|window| window.is_resizable())
+        .or_log(false)
+}
+
+/// The size is in physical pixels, a dimension of 0 is not constrained and a size of 0x0
+/// removes the constraint. Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_min_surface_size(
+    window: *mut ValueBox<WindowHandle>,
+    width: u32,
+    height: u32,
 ) {
     window
         .with_ref_ok(|window| {
-            window.request_surface_size(Size::Physical(PhysicalSize::new(width, height)));
+            window.set_min_surface_size(surface_size_constraint(width, height, 0))
         })
         .log();
 }
 
+/// The size is in physical pixels, a dimension of 0 is not constrained and a size of 0x0
+/// removes the constraint.
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_max_surface_size(
+    window: *mut ValueBox<WindowHandle>,
+    width: u32,
+    height: u32,
+) {
+    window
+        .with_ref_ok(|window| {
+            window.set_max_surface_size(surface_size_constraint(width, height, u32::MAX))
+        })
+        .log();
+}
+
+/// A dimension of 0 is not constrained, None once neither dimension is
+fn surface_size_constraint(width: u32, height: u32, unconstrained: u32) -> Option<Size> {
+    let dimension = |dimension: u32| {
+        if dimension == 0 {
+            unconstrained
+        } else {
+            dimension
+        }
+    };
+    (width > 0 || height > 0)
+        .then(|| Size::Physical(PhysicalSize::new(dimension(width), dimension(height))))
+}
+
+/// Same as [`winit_window_handle_request_surface_size`], the acknowledgement is fired once the
+/// size was requested and is consumed, or right away if it can not be requested.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_request_surface_size_acknowledged(
+    window: *mut ValueBox<WindowHandle>,
+    width: u32,
+    height: u32,
+    acknowledgement: *mut ValueBox<ActionAcknowledgement>,
+) -> WinitErrorCode {
+    with_acknowledgement(acknowledgement, |acknowledgement| {
+        window
+            .with_ref_ok(|window| {
+                window.request_surface_size(
+                    Size::Physical(PhysicalSize::new(width, height)),
+                    acknowledgement.take(),
+                )
+            })
+            .map_err(WinitError::from)
+            .and_then(|result| result)
+    })
+}
+
+/// Change the title of the window, it is also kept when the window is recreated.
 /// Must be called from a UI thread
 #[no_mangle]
 pub extern "C" fn winit_window_handle_set_title(
@@ -318,8 +1396,25 @@ pub extern "C" fn winit_window_handle_set_title(
         .log();
 }
 
+/// Set the title of the window showing a document, marked according to the platform
+/// conventions when the document is modified.
 /// Must be called from a UI thread
 #[no_mangle]
+pub extern "C" fn winit_window_handle_set_document_state(
+    window: *mut ValueBox<WindowHandle>,
+    title: *mut ValueBox<StringBox>,
+    is_modified: bool,
+) {
+    window
+        .with_ref(|window| {
+            title.with_ref_ok(|title| window.set_document_state(title.as_str(), is_modified))
+        })
+        .log();
+}
+
+/// Read the current title of the window into the string, including titles changed by the
+/// platform. Must be called from a UI thread
+#[no_mangle]
 pub extern "C" fn winit_window_handle_get_title(
     window: *mut ValueBox<WindowHandle>,
     title: *mut ValueBox<StringBox>,
@@ -335,18 +1430,74 @@ pub extern "C" fn winit_window_handle_get_title(
         .log();
 }
 
+/// Check if the key with the scan code, as reported by keyboard events, is currently held
+/// down in the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_handle_is_key_pressed(
+    window: *mut ValueBox<WindowHandle>,
+    scan_code: u32,
+) -> bool {
+    window
+        .with_ref_ok(|window| window.is_key_pressed(scan_code))
+        .or_log(false)
+}
+
+/// Recreate the native window that was lost, for example after a GPU reset or a compositor
+/// crash. The window keeps its id, a `Winit30WindowEventRecreated` event with the new raw
+/// handles is enqueued once it is recreated. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_handle_recreate(
+    window: *mut ValueBox<WindowHandle>,
+) -> WinitErrorCode {
+    window
+        .with_ref_ok(|window| window.request_recreate(None))
+        .map_err(WinitError::from)
+        .and_then(|result| result)
+        .into_error_code()
+}
+
+/// Same as [`winit_window_handle_recreate`], the acknowledgement is fired once the window was
+/// recreated and is consumed, or right away if it can not be requested.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_recreate_acknowledged(
+    window: *mut ValueBox<WindowHandle>,
+    acknowledgement: *mut ValueBox<ActionAcknowledgement>,
+) -> WinitErrorCode {
+    with_acknowledgement(acknowledgement, |acknowledgement| {
+        window
+            .with_ref_ok(|window| window.request_recreate(acknowledgement.take()))
+            .map_err(WinitError::from)
+            .and_then(|result| result)
+    })
+}
+
+/// Post the caret area in physical pixels, relative to the window surface. The IME candidate
+/// window follows it when enabled with
+/// [`crate::winit_application_handle_set_follow_ime_caret`]. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_handle_post_ime_caret_area(
+    window: *mut ValueBox<WindowHandle>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) {
+    window
+        .with_ref_ok(|window| {
+            window.post_ime_caret_area(
+                PhysicalPosition::new(x, y),
+                PhysicalSize::new(width, height),
+            )
+        })
+        .log();
+}
+
 /// Must be called from a UI thread
 #[no_mangle]
 pub extern "C" fn winit_window_handle_request_redraw(window: *mut ValueBox<WindowHandle>) {
     window
-        .with_ref(|window| {
-            window
-                .window
-                .lock()
-                .as_ref()
-                .ok_or_else(|| anyhow!("Window is closed").into())
-                .map(|window| window.request_redraw())
-        })
+        .with_ref(|window| window.request_redraw().map_err(|error| error.into()))
         .log();
 }
 
@@ -355,12 +1506,12 @@ pub extern "C" fn winit_window_handle_add_redraw_listener(
     window: *mut ValueBox<WindowHandle>,
     callback: unsafe extern "C" fn(*const c_void),
     thunk: *const c_void,
-) {
+) -> usize {
     window
         .with_ref_ok(|window| {
-            window.add_redraw_listener(WindowRedrawRequestedListener::new(callback, thunk));
+            window.add_redraw_listener(WindowRedrawRequestedListener::new(callback, thunk))
         })
-        .log();
+        .or_log(0)
 }
 
 #[no_mangle]
@@ -368,20 +1519,134 @@ pub extern "C" fn winit_window_handle_add_resize_listener(
     window: *mut ValueBox<WindowHandle>,
     callback: unsafe extern "C" fn(*const c_void, u32, u32),
     thunk: *const c_void,
-) {
+) -> usize {
     window
         .with_ref_ok(|window| {
-            window.add_resized_listener(WindowResizedListener::new(callback, thunk));
+            window.add_resized_listener(WindowResizedListener::new(callback, thunk))
         })
+        .or_log(0)
+}
+
+/// Let the callback decide whether the window is closed when the user asks to close it.
+/// The window is destroyed unless the callback returns false, the close requested event is
+/// enqueued either way
+#[no_mangle]
+pub extern "C" fn winit_window_handle_add_close_listener(
+    window: *mut ValueBox<WindowHandle>,
+    callback: unsafe extern "C" fn(*const c_void) -> bool,
+    thunk: *const c_void,
+) -> usize {
+    window
+        .with_ref_ok(|window| {
+            window.add_close_listener(WindowCloseRequestedListener::new(callback, thunk))
+        })
+        .or_log(0)
+}
+
+/// The callback receives (thunk, is_focused)
+#[no_mangle]
+pub extern "C" fn winit_window_handle_add_focus_listener(
+    window: *mut ValueBox<WindowHandle>,
+    callback: unsafe extern "C" fn(*const c_void, bool),
+    thunk: *const c_void,
+) -> usize {
+    window
+        .with_ref_ok(|window| window.add_focus_listener(WindowFocusListener::new(callback, thunk)))
+        .or_log(0)
+}
+
+/// The callback receives (thunk, scale_factor, width, height)
+#[no_mangle]
+pub extern "C" fn winit_window_handle_add_scale_factor_listener(
+    window: *mut ValueBox<WindowHandle>,
+    callback: unsafe extern "C" fn(*const c_void, f64, u32, u32),
+    thunk: *const c_void,
+) -> usize {
+    window
+        .with_ref_ok(|window| {
+            window.add_scale_factor_listener(WindowScaleFactorChangedListener::new(callback, thunk))
+        })
+        .or_log(0)
+}
+
+/// Declare the kinds of content that can be dropped onto the window, as a mask of
+/// [`WinitDropPayloadType`]. Drops of other kinds are ignored, hover events tell whether the
+/// dragged content is accepted.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_accepted_drop_types(
+    window: *mut ValueBox<WindowHandle>,
+    payload_types: u32,
+) {
+    window
+        .with_ref_ok(|window| window.set_accepted_drop_types(payload_types))
         .log();
 }
 
+/// Remove a listener by the id returned when it was added.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_remove_listener(
+    window: *mut ValueBox<WindowHandle>,
+    listener_id: usize,
+) -> bool {
+    window
+        .with_ref_ok(|window| window.remove_listener(listener_id))
+        .or_log(false)
+}
+
 /// Must be called from a UI thread
 #[no_mangle]
 pub extern "C" fn winit_window_handle_focus_window(window: *mut ValueBox<WindowHandle>) {
     window.with_ref_ok(|window| window.focus_window()).log();
 }
 
+/// Same as [`winit_window_handle_focus_window`], but the window is focused once the request
+/// reaches the UI thread, before the queued background actions. Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_request_focus(window: *mut ValueBox<WindowHandle>) {
+    window.with_ref_ok(|window| window.request_focus()).log();
+}
+
+/// Make the window borderless fullscreen on the monitor, or on the monitor the window is on
+/// if the monitor is null. Fails with an invalid handle if the monitor box is stale.
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_set_fullscreen_borderless(
+    window: *mut ValueBox<WindowHandle>,
+    monitor: *mut ValueBox<MonitorHandle>,
+) -> WinitErrorCode {
+    let monitor = if monitor.is_null() {
+        Ok(None)
+    } else {
+        monitor
+            .with_ref_ok(|monitor| Some(monitor.clone()))
+            .map_err(WinitError::from)
+    };
+
+    monitor
+        .and_then(|monitor| {
+            window
+                .with_ref_ok(|window| window.set_fullscreen_borderless(monitor))
+                .map_err(WinitError::from)
+        })
+        .into_error_code()
+}
+
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_exit_fullscreen(window: *mut ValueBox<WindowHandle>) {
+    window.with_ref_ok(|window| window.exit_fullscreen()).log();
+}
+
+/// Must be called from a UI thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_is_fullscreen(window: *mut ValueBox<WindowHandle>) -> bool {
+    window
+        .with_ref_ok(|window| window.is_fullscreen())
+        .or_log(false)
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_handle_current_monitor(
     window: *mut ValueBox<WindowHandle>,
@@ -555,7 +1820,68 @@ pub extern "C" fn winit_window_handle_get_wayland_display(
     })
 }
 
+/// Request an activation token, delivered as a `WinitActivationTokenDoneEvent`.
+/// Must be called from a UI thread
+#[cfg(any(x11_platform, wayland_platform))]
+#[no_mangle]
+pub extern "C" fn winit_window_handle_request_activation_token(
+    window: *mut ValueBox<WindowHandle>,
+) -> bool {
+    use winit::platform::startup_notify::WindowExtStartupNotify;
+
+    window
+        .with_ref(|window| {
+            window
+                .window
+                .lock()
+                .as_ref()
+                .ok_or_else(|| anyhow!("Window is closed").into())
+                .and_then(|window| {
+                    window
+                        .request_activation_token()
+                        .map(|_| true)
+                        .map_err(|error| anyhow!(error).into())
+                })
+        })
+        .or_log(false)
+}
+
 #[no_mangle]
 pub fn winit_window_handle_release(window_handle: *mut ValueBox<WindowHandle>) {
     window_handle.release();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_zero_dimensions_unconstrained() {
+        assert_eq!(
+            surface_size_constraint(0, 600, u32::MAX),
+            Some(Size::Physical(PhysicalSize::new(u32::MAX, 600)))
+        );
+        assert_eq!(
+            surface_size_constraint(800, 0, 0),
+            Some(Size::Physical(PhysicalSize::new(800, 0)))
+        );
+        assert_eq!(surface_size_constraint(0, 0, u32::MAX), None);
+    }
+
+    #[test]
+    fn drops_listeners_removed_while_called() {
+        let called_listeners = vec![(1, "removed"), (2, "kept")];
+        let mut listeners = vec![(3, "added")];
+        let listener_ids = HashSet::from([2, 3]);
+
+        restore_listeners(called_listeners, &mut listeners, &listener_ids);
+        assert_eq!(listeners, vec![(2, "kept"), (3, "added")]);
+    }
+
+    #[test]
+    fn marks_modified_documents() {
+        assert_eq!(document_title("notes.txt", false), "notes.txt");
+        assert_ne!(document_title("notes.txt", true), "notes.txt");
+        assert!(document_title("notes.txt", true).ends_with("notes.txt"));
+    }
+}
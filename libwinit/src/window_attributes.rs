@@ -1,30 +1,106 @@
-use winit::dpi::LogicalSize;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size};
 
 use string_box::StringBox;
 use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::event_loop::ActiveEventLoop;
+use winit::monitor::MonitorHandle;
 use winit::window::{WindowAttributes, WindowLevel};
 
+/// Window attributes together with the properties that can only be resolved
+/// when the window is created on the UI thread.
+#[derive(Default)]
+pub struct WinitWindowAttributes {
+    pub attributes: WindowAttributes,
+    pub placement: WindowPlacement,
+}
+
+impl WinitWindowAttributes {
+    pub fn map(self, f: impl FnOnce(WindowAttributes) -> WindowAttributes) -> Self {
+        Self {
+            attributes: f(self.attributes),
+            placement: self.placement,
+        }
+    }
+
+    /// Resolve the attributes to be used to create a window
+    pub fn resolve(self, event_loop: &dyn ActiveEventLoop) -> WindowAttributes {
+        match self.placement {
+            WindowPlacement::Default => self.attributes,
+            WindowPlacement::Centered(monitor) => {
+                match centered_position(event_loop, &self.attributes, monitor) {
+                    Some(position) => self.attributes.with_position(position),
+                    None => self.attributes,
+                }
+            }
+        }
+    }
+}
+
+impl From<WindowAttributes> for WinitWindowAttributes {
+    fn from(attributes: WindowAttributes) -> Self {
+        Self {
+            attributes,
+            placement: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WindowPlacement {
+    /// Let the platform decide where to put the window, or use the explicit position
+    Default,
+    /// Center the window on a given monitor, or the primary one
+    Centered(Option<MonitorHandle>),
+}
+
+impl Default for WindowPlacement {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+fn centered_position(
+    event_loop: &dyn ActiveEventLoop,
+    attributes: &WindowAttributes,
+    monitor: Option<MonitorHandle>,
+) -> Option<PhysicalPosition<i32>> {
+    let monitor = monitor.or_else(|| event_loop.primary_monitor())?;
+    let monitor_position = monitor.position()?;
+    let monitor_size = monitor.current_video_mode()?.size();
+    let scale_factor = monitor.scale_factor();
+
+    // winit does not expose the platform default size, assume the most common one
+    let window_size: PhysicalSize<u32> = attributes
+        .surface_size
+        .unwrap_or(Size::Logical(LogicalSize::new(800.0, 600.0)))
+        .to_physical(scale_factor);
+
+    let x = monitor_position.x + (monitor_size.width as i32 - window_size.width as i32) / 2;
+    let y = monitor_position.y + (monitor_size.height as i32 - window_size.height as i32) / 2;
+    Some(PhysicalPosition::new(x, y))
+}
+
 #[no_mangle]
-pub extern "C" fn winit_window_attributes_new() -> *mut ValueBox<WindowAttributes> {
-    ValueBox::new(WindowAttributes::default()).into_raw()
+pub extern "C" fn winit_window_attributes_new() -> *mut ValueBox<WinitWindowAttributes> {
+    ValueBox::new(WinitWindowAttributes::default()).into_raw()
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_release(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
 ) {
     window_attributes.release();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_title(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     window_title: *mut ValueBox<StringBox>,
 ) {
     window_title
         .with_ref_ok(|window_title| {
             window_attributes.replace_value(|window_attributes| {
-                window_attributes.with_title(window_title.to_string())
+                window_attributes.map(|attributes| attributes.with_title(window_title.to_string()))
             })
         })
         .log();
@@ -32,70 +108,81 @@ pub extern "C" fn winit_window_attributes_with_title(
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_decorations(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_decorations: bool,
 ) {
     window_attributes
-        .replace_value(|window_attributes| window_attributes.with_decorations(with_decorations))
+        .replace_value(|window_attributes| {
+            window_attributes.map(|attributes| attributes.with_decorations(with_decorations))
+        })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_transparency(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_transparency: bool,
 ) {
     window_attributes
-        .replace_value(|window_attributes| window_attributes.with_transparent(with_transparency))
+        .replace_value(|window_attributes| {
+            window_attributes.map(|attributes| attributes.with_transparent(with_transparency))
+        })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_resizable(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_resizable: bool,
 ) {
     window_attributes
-        .replace_value(|window_attributes| window_attributes.with_resizable(with_resizable))
+        .replace_value(|window_attributes| {
+            window_attributes.map(|attributes| attributes.with_resizable(with_resizable))
+        })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_dimensions(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     width: f64,
     height: f64,
 ) {
     window_attributes
         .replace_value(|window_attributes| {
-            window_attributes.with_surface_size(LogicalSize::new(width, height))
+            window_attributes
+                .map(|attributes| attributes.with_surface_size(LogicalSize::new(width, height)))
         })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_maximized(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_maximized: bool,
 ) {
     window_attributes
-        .replace_value(|window_attributes| window_attributes.with_maximized(with_maximized))
+        .replace_value(|window_attributes| {
+            window_attributes.map(|attributes| attributes.with_maximized(with_maximized))
+        })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_visibility(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_visibility: bool,
 ) {
     window_attributes
-        .replace_value(|window_attributes| window_attributes.with_visible(with_visibility))
+        .replace_value(|window_attributes| {
+            window_attributes.map(|attributes| attributes.with_visible(with_visibility))
+        })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_always_on_top(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
     with_always_on_top: bool,
 ) {
     window_attributes
@@ -104,35 +191,63 @@ pub extern "C" fn winit_window_attributes_with_always_on_top(
                 true => WindowLevel::AlwaysOnTop,
                 false => WindowLevel::Normal,
             };
-            window_attributes.with_window_level(level)
+            window_attributes.map(|attributes| attributes.with_window_level(level))
         })
         .log();
 }
 
-#[cfg(not(target_os = "macos"))]
 #[no_mangle]
 pub extern "C" fn winit_window_attributes_with_full_size(
-    _ptr_window_attributes: *mut ValueBox<WindowAttributes>,
-    _with_full_size: bool,
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    with_full_size: bool,
 ) {
+    window_attributes
+        .replace_value(|window_attributes| {
+            window_attributes
+                .map(|attributes| window_attributes_with_full_size(attributes, with_full_size))
+        })
+        .log();
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn window_attributes_with_full_size(
+    window_attributes: WindowAttributes,
+    _with_full_size: bool,
+) -> WindowAttributes {
+    window_attributes
 }
 
 #[cfg(target_os = "macos")]
-#[no_mangle]
-pub extern "C" fn winit_window_attributes_with_full_size(
-    window_attributes: *mut ValueBox<WindowAttributes>,
+pub(crate) fn window_attributes_with_full_size(
+    window_attributes: WindowAttributes,
     with_full_size: bool,
-) {
+) -> WindowAttributes {
     use winit::platform::macos::WindowAttributesMacOS;
 
-    window_attributes
-        .replace_value(|window_attributes| {
-            let macos_attributes = WindowAttributesMacOS::default()
-                .with_titlebar_transparent(with_full_size)
-                .with_title_hidden(with_full_size)
-                .with_fullsize_content_view(with_full_size);
+    let macos_attributes = WindowAttributesMacOS::default()
+        .with_titlebar_transparent(with_full_size)
+        .with_title_hidden(with_full_size)
+        .with_fullsize_content_view(with_full_size);
 
-            window_attributes.with_platform_attributes(Box::new(macos_attributes))
+    window_attributes.with_platform_attributes(Box::new(macos_attributes))
+}
+
+/// Center the window on a given monitor when it is created.
+/// If the monitor is null, the window is centered on the primary monitor.
+#[no_mangle]
+pub extern "C" fn winit_window_attributes_with_centered(
+    window_attributes: *mut ValueBox<WinitWindowAttributes>,
+    monitor: *mut ValueBox<MonitorHandle>,
+) {
+    let monitor = if monitor.is_null() {
+        None
+    } else {
+        monitor.with_ref_ok(|monitor| monitor.clone()).ok()
+    };
+
+    window_attributes
+        .with_mut_ok(|window_attributes| {
+            window_attributes.placement = WindowPlacement::Centered(monitor);
         })
         .log();
 }
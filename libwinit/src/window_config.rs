@@ -0,0 +1,145 @@
+use crate::{
+    with_acknowledgement, ActionAcknowledgement, ReturnErrorCode, WindowHandle, WinitCursorIcon,
+    WinitError, WinitErrorCode,
+};
+use string_box::StringBox;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::cursor::CursorIcon;
+use winit::dpi::{PhysicalSize, Size};
+use winit::window::WindowLevel;
+
+/// A set of window properties applied at once on the UI thread.
+/// Properties set to `None` are left unchanged.
+#[derive(Debug, Default)]
+pub struct WindowConfig {
+    pub title: Option<String>,
+    pub surface_size: Option<Size>,
+    pub min_surface_size: Option<Size>,
+    pub max_surface_size: Option<Size>,
+    pub resizable: Option<bool>,
+    pub decorations: Option<bool>,
+    pub level: Option<WindowLevel>,
+    pub cursor: Option<CursorIcon>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WinitWindowLevel {
+    AlwaysOnBottom,
+    Normal,
+    AlwaysOnTop,
+}
+
+impl From<WinitWindowLevel> for WindowLevel {
+    fn from(level: WinitWindowLevel) -> Self {
+        match level {
+            WinitWindowLevel::AlwaysOnBottom => WindowLevel::AlwaysOnBottom,
+            WinitWindowLevel::Normal => WindowLevel::Normal,
+            WinitWindowLevel::AlwaysOnTop => WindowLevel::AlwaysOnTop,
+        }
+    }
+}
+
+/// A window configuration as filled in by the host.
+/// Every property is accompanied by a `has_` flag telling if it should be applied.
+/// Sizes are in physical pixels. The title is borrowed and must be released by the host.
+#[derive(Debug)]
+#[repr(C)]
+pub struct WinitWindowConfig {
+    title: *mut ValueBox<StringBox>,
+    has_surface_size: bool,
+    width: u32,
+    height: u32,
+    has_min_surface_size: bool,
+    min_width: u32,
+    min_height: u32,
+    has_max_surface_size: bool,
+    max_width: u32,
+    max_height: u32,
+    has_resizable: bool,
+    resizable: bool,
+    has_decorations: bool,
+    decorations: bool,
+    has_level: bool,
+    level: WinitWindowLevel,
+    has_cursor: bool,
+    cursor: WinitCursorIcon,
+}
+
+impl From<&WinitWindowConfig> for WindowConfig {
+    fn from(config: &WinitWindowConfig) -> Self {
+        fn physical_size(has_size: bool, width: u32, height: u32) -> Option<Size> {
+            has_size.then(|| Size::Physical(PhysicalSize::new(width, height)))
+        }
+
+        let title = if config.title.is_null() {
+            None
+        } else {
+            config
+                .title
+                .with_ref_ok(|title| title.to_string())
+                .map(Some)
+                .or_log(None)
+        };
+
+        Self {
+            title,
+            surface_size: physical_size(config.has_surface_size, config.width, config.height),
+            min_surface_size: physical_size(
+                config.has_min_surface_size,
+                config.min_width,
+                config.min_height,
+            ),
+            max_surface_size: physical_size(
+                config.has_max_surface_size,
+                config.max_width,
+                config.max_height,
+            ),
+            resizable: config.has_resizable.then_some(config.resizable),
+            decorations: config.has_decorations.then_some(config.decorations),
+            level: config.has_level.then(|| config.level.into()),
+            cursor: config.has_cursor.then(|| config.cursor.into()),
+        }
+    }
+}
+
+/// Apply a batch of window properties in one action on the UI thread.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_apply_config(
+    window: *mut ValueBox<WindowHandle>,
+    config: *const WinitWindowConfig,
+) -> WinitErrorCode {
+    window_config(config)
+        .and_then(|config| {
+            window
+                .with_ref_ok(|window| window.enqueue_config(config, None))
+                .map_err(WinitError::from)
+                .and_then(|result| result)
+        })
+        .into_error_code()
+}
+
+/// Same as [`winit_window_handle_apply_config`], the acknowledgement is fired once the config
+/// was applied and is consumed, or right away if it can not be requested.
+/// Can be called from any thread
+#[no_mangle]
+pub extern "C" fn winit_window_handle_apply_config_acknowledged(
+    window: *mut ValueBox<WindowHandle>,
+    config: *const WinitWindowConfig,
+    acknowledgement: *mut ValueBox<ActionAcknowledgement>,
+) -> WinitErrorCode {
+    with_acknowledgement(acknowledgement, |acknowledgement| {
+        let config = window_config(config)?;
+        window
+            .with_ref_ok(|window| window.enqueue_config(config, acknowledgement.take()))
+            .map_err(WinitError::from)
+            .and_then(|result| result)
+    })
+}
+
+fn window_config(config: *const WinitWindowConfig) -> Result<WindowConfig, WinitError> {
+    unsafe { config.as_ref() }
+        .map(WindowConfig::from)
+        .ok_or_else(|| WinitError::new(WinitErrorCode::InvalidArgument, "Window config is null"))
+}
@@ -0,0 +1,105 @@
+use crate::window_attributes::window_attributes_with_full_size;
+use crate::WinitWindowAttributes;
+use serde::{Deserialize, Serialize};
+use string_box::StringBox;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::dpi::{LogicalPosition, LogicalSize};
+use winit::window::{WindowAttributes, WindowLevel};
+
+/// A serializable description of window attributes, allowing hosts to store window templates as
+/// data. Missing properties keep their default values. Sizes and positions are logical.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowProfile {
+    pub title: Option<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub min_width: Option<f64>,
+    pub min_height: Option<f64>,
+    pub max_width: Option<f64>,
+    pub max_height: Option<f64>,
+    pub decorations: Option<bool>,
+    pub transparent: Option<bool>,
+    pub resizable: Option<bool>,
+    pub maximized: Option<bool>,
+    pub visible: Option<bool>,
+    pub always_on_top: Option<bool>,
+    pub full_size: Option<bool>,
+}
+
+impl WindowProfile {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn into_window_attributes(self) -> WindowAttributes {
+        let mut window_attributes = WindowAttributes::default();
+
+        if let Some(title) = self.title {
+            window_attributes = window_attributes.with_title(title);
+        }
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            window_attributes =
+                window_attributes.with_surface_size(LogicalSize::new(width, height));
+        }
+        if let (Some(x), Some(y)) = (self.x, self.y) {
+            window_attributes = window_attributes.with_position(LogicalPosition::new(x, y));
+        }
+        if let (Some(width), Some(height)) = (self.min_width, self.min_height) {
+            window_attributes =
+                window_attributes.with_min_surface_size(LogicalSize::new(width, height));
+        }
+        if let (Some(width), Some(height)) = (self.max_width, self.max_height) {
+            window_attributes =
+                window_attributes.with_max_surface_size(LogicalSize::new(width, height));
+        }
+        if let Some(decorations) = self.decorations {
+            window_attributes = window_attributes.with_decorations(decorations);
+        }
+        if let Some(transparent) = self.transparent {
+            window_attributes = window_attributes.with_transparent(transparent);
+        }
+        if let Some(resizable) = self.resizable {
+            window_attributes = window_attributes.with_resizable(resizable);
+        }
+        if let Some(maximized) = self.maximized {
+            window_attributes = window_attributes.with_maximized(maximized);
+        }
+        if let Some(visible) = self.visible {
+            window_attributes = window_attributes.with_visible(visible);
+        }
+        if let Some(always_on_top) = self.always_on_top {
+            let level = match always_on_top {
+                true => WindowLevel::AlwaysOnTop,
+                false => WindowLevel::Normal,
+            };
+            window_attributes = window_attributes.with_window_level(level);
+        }
+        if let Some(full_size) = self.full_size {
+            window_attributes = window_attributes_with_full_size(window_attributes, full_size);
+        }
+
+        window_attributes
+    }
+}
+
+/// Build window attributes from a JSON window profile.
+/// Returns null if the profile can not be parsed.
+#[no_mangle]
+pub extern "C" fn winit_window_attributes_from_json(
+    json: *mut ValueBox<StringBox>,
+) -> *mut ValueBox<WinitWindowAttributes> {
+    json.with_ref(|json| {
+        WindowProfile::from_json(json.as_str())
+            .map(|profile| {
+                ValueBox::new(WinitWindowAttributes::from(
+                    profile.into_window_attributes(),
+                ))
+                .into_raw()
+            })
+            .map_err(|error| error.into())
+    })
+    .or_log(std::ptr::null_mut())
+}